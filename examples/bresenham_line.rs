@@ -71,7 +71,7 @@ pub fn main() {
             0,
             WINDOW_WIDTH as i32,
             WINDOW_HEIGHT as i32,
-            fltk::enums::ColorDepth::Rgb8,
+            fltk::enums::ColorDepth::Rgba8,
         )
         .unwrap();
     });
@@ -7,12 +7,11 @@ use fltk::{
 use tiny_renderer::{
     camera::Camera,
     color::Color,
-    light::PointLight,
+    light::{AmbientLight, Light, PointLight},
     loader::load_glft,
     math::{Quat, Vec3},
     renderer::{Renderer, RendererSettings, Viewport},
     shader::phong_shader,
-    transform::translation_mat4,
     util::{custom_cube, flip_vertically, rand_color},
 };
 
@@ -50,14 +49,17 @@ pub fn main() {
 
     // let (meshes, texture_storage) = custom_cube();
     let mut model_index = 0;
-    let (mut meshes, mut texture_storage) = load_glft(MODEL_LIST[model_index % MODEL_LIST.len()]);
+    let (mut scene, mut texture_storage) =
+        load_glft(MODEL_LIST[model_index % MODEL_LIST.len()]).unwrap();
     let model_pos = Vec3::new(0.0, 0.0, 0.0);
-    let model_transformation = translation_mat4(model_pos);
 
-    let light = PointLight {
-        position: Vec3::new(-5.0, 5.0, 5.0),
-        intensity: 100.0,
-    };
+    let lights = [
+        Light::Ambient(AmbientLight { intensity: 0.2 }),
+        Light::Point(PointLight {
+            position: Vec3::new(-5.0, 5.0, 5.0),
+            intensity: 100.0,
+        }),
+    ];
 
     let mut camera = Camera::new(
         5.0,
@@ -101,7 +103,8 @@ pub fn main() {
         }
         if event_key_down(Key::F5) {
             model_index += 1;
-            (meshes, texture_storage) = load_glft(MODEL_LIST[model_index % MODEL_LIST.len()]);
+            (scene, texture_storage) =
+                load_glft(MODEL_LIST[model_index % MODEL_LIST.len()]).unwrap();
         }
         if event_key_down(Key::from_char('A')) {
             renderer
@@ -135,7 +138,7 @@ pub fn main() {
         }
 
         renderer.clear();
-        renderer.draw(&meshes, model_transformation, light, &texture_storage);
+        renderer.draw_scene(&scene, &lights, &texture_storage);
         fltk::draw::draw_image(
             &flip_vertically(
                 &renderer.frame_buffer,
@@ -146,7 +149,7 @@ pub fn main() {
             0,
             WINDOW_WIDTH as i32,
             WINDOW_HEIGHT as i32,
-            fltk::enums::ColorDepth::Rgb8,
+            fltk::enums::ColorDepth::Rgba8,
         )
         .unwrap();
     });
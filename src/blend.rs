@@ -0,0 +1,119 @@
+use crate::color::Color;
+
+// 混合因子，src为片段颜色，dst为帧缓冲中已有的颜色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+impl BlendFactor {
+    // 因子的(r, g, b, a)
+    pub fn factor(&self, src: Color, dst: Color) -> Color {
+        match self {
+            BlendFactor::Zero => Color::TRANSPARENT,
+            BlendFactor::One => Color::rgba(1.0, 1.0, 1.0, 1.0),
+            BlendFactor::SrcColor => src,
+            BlendFactor::OneMinusSrcColor => one_minus(src),
+            BlendFactor::DstColor => dst,
+            BlendFactor::OneMinusDstColor => one_minus(dst),
+            BlendFactor::SrcAlpha => Color::rgba(src.a, src.a, src.a, src.a),
+            BlendFactor::OneMinusSrcAlpha => one_minus(Color::rgba(src.a, src.a, src.a, src.a)),
+            BlendFactor::DstAlpha => Color::rgba(dst.a, dst.a, dst.a, dst.a),
+            BlendFactor::OneMinusDstAlpha => one_minus(Color::rgba(dst.a, dst.a, dst.a, dst.a)),
+        }
+    }
+}
+
+// 混合运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendOperation {
+    // src * src_factor + dst * dst_factor
+    Add,
+    // src * src_factor - dst * dst_factor
+    Subtract,
+    // dst * dst_factor - src * src_factor
+    ReverseSubtract,
+    // min(src, dst)，忽略混合因子
+    Min,
+    // max(src, dst)，忽略混合因子
+    Max,
+}
+impl BlendOperation {
+    pub fn apply(&self, src: f32, src_factor: f32, dst: f32, dst_factor: f32) -> f32 {
+        match self {
+            BlendOperation::Add => src * src_factor + dst * dst_factor,
+            BlendOperation::Subtract => src * src_factor - dst * dst_factor,
+            BlendOperation::ReverseSubtract => dst * dst_factor - src * src_factor,
+            BlendOperation::Min => src.min(dst),
+            BlendOperation::Max => src.max(dst),
+        }
+    }
+}
+
+// 混合方程，颜色通道和透明度通道分别设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlendState {
+    pub color_src_factor: BlendFactor,
+    pub color_dst_factor: BlendFactor,
+    pub color_operation: BlendOperation,
+    pub alpha_src_factor: BlendFactor,
+    pub alpha_dst_factor: BlendFactor,
+    pub alpha_operation: BlendOperation,
+}
+impl BlendState {
+    // 标准的透明度混合（over）：color = src * src.a + dst * (1 - src.a)
+    pub const ALPHA_BLENDING: Self = Self {
+        color_src_factor: BlendFactor::SrcAlpha,
+        color_dst_factor: BlendFactor::OneMinusSrcAlpha,
+        color_operation: BlendOperation::Add,
+        alpha_src_factor: BlendFactor::One,
+        alpha_dst_factor: BlendFactor::OneMinusSrcAlpha,
+        alpha_operation: BlendOperation::Add,
+    };
+    // 叠加混合：color = src * src.a + dst
+    pub const ADDITIVE: Self = Self {
+        color_src_factor: BlendFactor::SrcAlpha,
+        color_dst_factor: BlendFactor::One,
+        color_operation: BlendOperation::Add,
+        alpha_src_factor: BlendFactor::One,
+        alpha_dst_factor: BlendFactor::One,
+        alpha_operation: BlendOperation::Add,
+    };
+
+    pub fn blend(&self, src: Color, dst: Color) -> Color {
+        let color_src_factor = self.color_src_factor.factor(src, dst);
+        let color_dst_factor = self.color_dst_factor.factor(src, dst);
+        let alpha_src_factor = self.alpha_src_factor.factor(src, dst);
+        let alpha_dst_factor = self.alpha_dst_factor.factor(src, dst);
+        let color = |src: f32, src_factor: f32, dst: f32, dst_factor: f32| {
+            self.color_operation
+                .apply(src, src_factor, dst, dst_factor)
+                .clamp(0.0, 1.0)
+        };
+        Color::rgba(
+            color(src.r, color_src_factor.r, dst.r, color_dst_factor.r),
+            color(src.g, color_src_factor.g, dst.g, color_dst_factor.g),
+            color(src.b, color_src_factor.b, dst.b, color_dst_factor.b),
+            self.alpha_operation
+                .apply(src.a, alpha_src_factor.a, dst.a, alpha_dst_factor.a)
+                .clamp(0.0, 1.0),
+        )
+    }
+}
+impl Default for BlendState {
+    fn default() -> Self {
+        Self::ALPHA_BLENDING
+    }
+}
+
+fn one_minus(color: Color) -> Color {
+    Color::rgba(1.0 - color.r, 1.0 - color.g, 1.0 - color.b, 1.0 - color.a)
+}
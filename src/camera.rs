@@ -4,6 +4,7 @@ use crate::{
 };
 
 //// 视椎体
+#[derive(Debug, Clone, Copy)]
 pub struct Frustum {
     // 垂直视野（弧度）
     pub fov: f32,
@@ -71,6 +72,7 @@ impl Frustum {
 }
 
 //// 相机
+#[derive(Debug, Clone, Copy)]
 pub struct Camera {
     pub frustum: Frustum,
     pub position: Vec3,
@@ -1,12 +1,14 @@
 use std::ops::{Add, Mul};
 
-use crate::math::Vec3;
+use crate::math::{Vec3, Vec4};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
     pub b: f32,
+    // 不透明度（1为完全不透明）
+    pub a: f32,
 }
 impl Color {
     pub const BLACK: Self = Self::new(0., 0., 0.);
@@ -15,8 +17,15 @@ impl Color {
     pub const BLUE: Self = Self::new(0., 0., 1.);
     pub const WHITE: Self = Self::new(1., 1., 1.);
 
+    pub const TRANSPARENT: Self = Self::rgba(0., 0., 0., 0.);
+
+    // 不透明颜色
     pub const fn new(r: f32, g: f32, b: f32) -> Self {
-        Self { r, g, b }
+        Self { r, g, b, a: 1.0 }
+    }
+
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
     }
 
     pub fn from_vec3(v: Vec3) -> Self {
@@ -29,6 +38,59 @@ impl Color {
     pub fn to_vec3(&self) -> Vec3 {
         Vec3::new(self.r, self.g, self.b)
     }
+
+    pub fn from_vec4(v: Vec4) -> Self {
+        Self::rgba(v.x, v.y, v.z, v.w)
+    }
+
+    pub fn to_vec4(&self) -> Vec4 {
+        Vec4::new(self.r, self.g, self.b, self.a)
+    }
+
+    // 相同颜色，不透明度为a
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    // 线性插值
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        Self::rgba(
+            self.r + (rhs.r - self.r) * t,
+            self.g + (rhs.g - self.g) * t,
+            self.b + (rhs.b - self.b) * t,
+            self.a + (rhs.a - self.a) * t,
+        )
+    }
+
+    // 转换为RGBA8：颜色通道从线性空间编码为sRGB，透明度保持线性
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| linear_to_srgb(c.clamp(0.0, 1.0)));
+        [r, g, b, self.a.clamp(0.0, 1.0)].map(|c| (c * 255.).round() as u8)
+    }
+
+    // 从RGBA8解码，to_rgba8的逆变换
+    pub fn from_rgba8(rgba: [u8; 4]) -> Self {
+        let [r, g, b, a] = rgba.map(|c| c as f32 / 255.);
+        Self::rgba(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+}
+
+// sRGB传递函数的逆变换（IEC 61966-2-1）
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// sRGB传递函数（IEC 61966-2-1），将线性值编码为sRGB
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
 }
 impl Default for Color {
     fn default() -> Self {
@@ -39,10 +101,11 @@ impl Add<Color> for Color {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self::new(
+        Self::rgba(
             (self.r + rhs.r).min(1.0),
             (self.g + rhs.g).min(1.0),
             (self.b + rhs.b).min(1.0),
+            (self.a + rhs.a).min(1.0),
         )
     }
 }
@@ -50,13 +113,18 @@ impl Mul<f32> for Color {
     type Output = Self;
 
     fn mul(self, rhs: f32) -> Self::Output {
-        Self::new(self.r * rhs, self.g * rhs, self.b * rhs)
+        Self::rgba(self.r * rhs, self.g * rhs, self.b * rhs, self.a * rhs)
     }
 }
 impl Mul<Color> for Color {
     type Output = Self;
     fn mul(self, rhs: Color) -> Self::Output {
-        Self::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b)
+        Self::rgba(
+            self.r * rhs.r,
+            self.g * rhs.g,
+            self.b * rhs.b,
+            self.a * rhs.a,
+        )
     }
 }
 impl From<[f32; 3]> for Color {
@@ -64,3 +132,8 @@ impl From<[f32; 3]> for Color {
         Color::new(v[0], v[1], v[2])
     }
 }
+impl From<[f32; 4]> for Color {
+    fn from(v: [f32; 4]) -> Self {
+        Color::rgba(v[0], v[1], v[2], v[3])
+    }
+}
@@ -0,0 +1,121 @@
+use crate::math::Vec2;
+
+// 顶点坐标吸附到1/256像素的定点数网格上
+pub const SUBPIXEL_BITS: u32 = 8;
+pub const SUBPIXEL_SCALE: i64 = 1 << SUBPIXEL_BITS;
+
+// 屏幕坐标转换为定点数（四舍五入到最近的子像素）
+pub fn snap(p: Vec2) -> (i64, i64) {
+    let scale = SUBPIXEL_SCALE as f32;
+    ((p.x * scale).round() as i64, (p.y * scale).round() as i64)
+}
+
+// 有向边v0 -> v1的边函数 E(x, y) = (v1 - v0) x (p - v0) = a * x + b * y + c（定点数）
+// 点在边的左侧时为正
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeFunction {
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+    // 左上填充规则：恰好落在边上的点只属于上边或左边所在的三角形
+    // 非左上边的边函数值需要大于0，即 E + bias >= 0
+    pub bias: i64,
+}
+impl EdgeFunction {
+    pub fn new(v0: (i64, i64), v1: (i64, i64)) -> Self {
+        let a = v0.1 - v1.1;
+        let b = v1.0 - v0.0;
+        let c = -(a * v0.0 + b * v0.1);
+        // y轴向上、逆时针的三角形中，上边从右指向左，左边向下
+        let top = v1.1 == v0.1 && v1.0 < v0.0;
+        let left = v1.1 < v0.1;
+        Self {
+            a,
+            b,
+            c,
+            bias: if top || left { 0 } else { -1 },
+        }
+    }
+
+    pub fn evaluate(&self, (x, y): (i64, i64)) -> i64 {
+        self.a * x + self.b * y + self.c
+    }
+
+    // 点是否在边的内侧（包括属于该边的边界点）
+    pub fn covers(&self, value: i64) -> bool {
+        value + self.bias >= 0
+    }
+}
+
+// 三角形的三条边函数，edges[i]为顶点i所对的边
+// edges[i]在点p的值除以面积的两倍即为p的重心坐标的第i个分量
+#[derive(Debug, Clone, Copy)]
+pub struct TriangleEdges {
+    pub edges: [EdgeFunction; 3],
+    // 面积的两倍（始终为正）
+    pub area: i64,
+}
+impl TriangleEdges {
+    // 顺时针的三角形反转边的方向，退化（面积为0）的三角形返回None
+    pub fn new(vertices: [Vec2; 3]) -> Option<Self> {
+        let [v0, v1, v2] = vertices.map(snap);
+        let edges = [
+            EdgeFunction::new(v1, v2),
+            EdgeFunction::new(v2, v0),
+            EdgeFunction::new(v0, v1),
+        ];
+        let area = edges[0].evaluate(v0);
+        if area > 0 {
+            Some(Self { edges, area })
+        } else if area < 0 {
+            Some(Self {
+                edges: [
+                    EdgeFunction::new(v2, v1),
+                    EdgeFunction::new(v0, v2),
+                    EdgeFunction::new(v1, v0),
+                ],
+                area: -area,
+            })
+        } else {
+            None
+        }
+    }
+
+    // 三条边函数在定点数坐标p处的值
+    pub fn evaluate(&self, p: (i64, i64)) -> [i64; 3] {
+        self.edges.map(|edge| edge.evaluate(p))
+    }
+
+    // 沿x、y方向移动一个像素时边函数值的增量
+    pub fn step_x(&self) -> [i64; 3] {
+        self.edges.map(|edge| edge.a * SUBPIXEL_SCALE)
+    }
+
+    pub fn step_y(&self) -> [i64; 3] {
+        self.edges.map(|edge| edge.b * SUBPIXEL_SCALE)
+    }
+
+    // 边函数值对应的点是否在三角形内（左上填充规则）
+    pub fn covers(&self, values: [i64; 3]) -> bool {
+        (0..3).all(|i| self.edges[i].covers(values[i]))
+    }
+
+    // 边函数值转换为重心坐标
+    pub fn barycentric(&self, values: [i64; 3]) -> (f32, f32, f32) {
+        let area = self.area as f32;
+        (
+            values[0] as f32 / area,
+            values[1] as f32 / area,
+            values[2] as f32 / area,
+        )
+    }
+}
+
+// 两组边函数值逐分量相加
+pub fn add(values: [i64; 3], step: [i64; 3]) -> [i64; 3] {
+    [
+        values[0] + step[0],
+        values[1] + step[1],
+        values[2] + step[2],
+    ]
+}
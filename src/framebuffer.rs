@@ -0,0 +1,147 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::util::flip_vertically;
+
+// 图片文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Tga,
+    Png,
+}
+impl ImageFormat {
+    // 根据文件扩展名推断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(Self::Ppm),
+            "tga" => Some(Self::Tga),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+// 帧缓冲（RGBA8，以左下角为原点，与Renderer::frame_buffer的布局一致）
+#[derive(Debug, Clone)]
+pub struct FrameBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+impl FrameBuffer {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+        assert_eq!(data.len(), (width * height * 4) as usize);
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    // 将深度缓冲归一化为灰度图（越近越亮，未写入深度的像素为黑色）
+    pub fn from_depth_buffer(depth_buffer: &[f32], width: u32, height: u32) -> Self {
+        assert_eq!(depth_buffer.len(), (width * height) as usize);
+        let written = || depth_buffer.iter().copied().filter(|z| *z != f32::MIN);
+        let min = written().fold(f32::MAX, f32::min);
+        let max = written().fold(f32::MIN, f32::max);
+        let range = if max > min { max - min } else { 1.0 };
+
+        let mut data = Vec::with_capacity(depth_buffer.len() * 4);
+        for &z in depth_buffer {
+            let gray = if z == f32::MIN {
+                0
+            } else {
+                // 保证写入过的像素与背景可区分
+                (((z - min) / range) * 254.0 + 1.0) as u8
+            };
+            data.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+        Self::new(width, height, data)
+    }
+
+    // 以左上角为原点的像素数据（大多数图片格式的行顺序）
+    pub fn top_down_data(&self) -> Vec<u8> {
+        flip_vertically(&self.data, self.width as usize, self.height as usize)
+    }
+
+    // 根据扩展名保存为PPM/TGA/PNG
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unsupported image format: {:?}", path),
+            )
+        })?;
+        self.save_with_format(path, format)
+    }
+
+    pub fn save_with_format<P: AsRef<Path>>(&self, path: P, format: ImageFormat) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            ImageFormat::Ppm => self.write_ppm(&mut writer)?,
+            ImageFormat::Tga => self.write_tga(&mut writer)?,
+            ImageFormat::Png => self.write_png(&mut writer)?,
+        }
+        writer.flush()
+    }
+
+    // 二进制PPM（P6），不支持透明度通道
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let rgb: Vec<u8> = self
+            .top_down_data()
+            .chunks_exact(4)
+            .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+            .collect();
+        writer.write_all(&rgb)
+    }
+
+    // 未压缩的真彩色TGA，原点在左下角，无需翻转
+    // 宽高以16位存储，超过65535时返回错误
+    pub fn write_tga<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (Ok(width), Ok(height)) = (u16::try_from(self.width), u16::try_from(self.height))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Image too large for TGA: {}x{} (max 65535x65535)",
+                    self.width, self.height
+                ),
+            ));
+        };
+        let mut header = [0u8; 18];
+        // 图像类型：未压缩真彩色
+        header[2] = 2;
+        header[12..14].copy_from_slice(&width.to_le_bytes());
+        header[14..16].copy_from_slice(&height.to_le_bytes());
+        // 每像素位数
+        header[16] = 32;
+        // 图像描述：8位透明度通道
+        header[17] = 8;
+        writer.write_all(&header)?;
+
+        // TGA像素按BGRA顺序存储
+        let bgra: Vec<u8> = self
+            .data
+            .chunks_exact(4)
+            .flat_map(|rgba| [rgba[2], rgba[1], rgba[0], rgba[3]])
+            .collect();
+        writer.write_all(&bgra)
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.top_down_data())?;
+        writer.finish()?;
+        Ok(())
+    }
+}
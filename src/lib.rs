@@ -1,12 +1,21 @@
+pub mod blend;
 pub mod camera;
 pub mod color;
+pub mod edge;
+pub mod framebuffer;
 pub mod light;
 pub mod loader;
 pub mod material;
 pub mod math;
 pub mod mesh;
+pub mod msaa;
+pub mod oit;
+pub mod postprocess;
 pub mod renderer;
+pub mod scene;
 pub mod shader;
+pub mod shadow;
 pub mod texture;
+pub mod tile;
 pub mod transform;
 pub mod util;
@@ -16,3 +16,123 @@ impl Default for PointLight {
         }
     }
 }
+
+// 平行光（如太阳光），没有衰减
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionalLight {
+    // 光线的照射方向
+    pub direction: Vec3,
+    // 光强度
+    pub intensity: f32,
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(-1.0, -1.0, -1.0).normalize(),
+            intensity: 1.0,
+        }
+    }
+}
+
+// 聚光灯，在内外锥角之间平滑衰减
+#[derive(Debug, Clone, Copy)]
+pub struct SpotLight {
+    pub position: Vec3,
+    // 光线的照射方向
+    pub direction: Vec3,
+    // 光强度
+    pub intensity: f32,
+    // 内锥角（半角，弧度），内锥角以内强度不衰减
+    pub inner_cone_angle: f32,
+    // 外锥角（半角，弧度），外锥角以外没有光照
+    pub outer_cone_angle: f32,
+}
+
+impl Default for SpotLight {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 5.0, 0.0),
+            direction: Vec3::NEG_Y,
+            intensity: 100.0,
+            inner_cone_angle: 0.0,
+            outer_cone_angle: std::f32::consts::FRAC_PI_4,
+        }
+    }
+}
+
+// 环境光
+#[derive(Debug, Clone, Copy)]
+pub struct AmbientLight {
+    // 光强度
+    pub intensity: f32,
+}
+
+impl Default for AmbientLight {
+    fn default() -> Self {
+        Self { intensity: 0.2 }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Light {
+    Point(PointLight),
+    Directional(DirectionalLight),
+    Spot(SpotLight),
+    Ambient(AmbientLight),
+}
+impl Light {
+    // 着色点处的入射光线向量（指向光源，已归一化）和光照强度，环境光返回None
+    pub fn incident(&self, pos: Vec3) -> Option<(Vec3, f32)> {
+        match self {
+            Light::Point(light) => {
+                let to_light = light.position - pos;
+                let r = to_light.length();
+                Some((to_light * r.recip(), light.intensity / (r * r)))
+            }
+            Light::Directional(light) => Some((-light.direction.normalize(), light.intensity)),
+            Light::Spot(light) => {
+                let to_light = light.position - pos;
+                let r = to_light.length();
+                let l = to_light * r.recip();
+                // 光线方向与聚光方向夹角的余弦，在内外锥角之间平滑过渡
+                let cos_theta = (-l).dot(light.direction.normalize());
+                let cos_inner = light.inner_cone_angle.cos();
+                let cos_outer = light.outer_cone_angle.cos();
+                let t =
+                    ((cos_theta - cos_outer) / (cos_inner - cos_outer).max(1e-4)).clamp(0.0, 1.0);
+                let falloff = t * t * (3.0 - 2.0 * t);
+                Some((l, light.intensity * falloff / (r * r)))
+            }
+            Light::Ambient(_) => None,
+        }
+    }
+
+    // 环境光强度（非环境光为0）
+    pub fn ambient_intensity(&self) -> f32 {
+        match self {
+            Light::Ambient(light) => light.intensity,
+            _ => 0.0,
+        }
+    }
+}
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Light::Point(light)
+    }
+}
+impl From<DirectionalLight> for Light {
+    fn from(light: DirectionalLight) -> Self {
+        Light::Directional(light)
+    }
+}
+impl From<SpotLight> for Light {
+    fn from(light: SpotLight) -> Self {
+        Light::Spot(light)
+    }
+}
+impl From<AmbientLight> for Light {
+    fn from(light: AmbientLight) -> Self {
+        Light::Ambient(light)
+    }
+}
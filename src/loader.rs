@@ -1,132 +1,387 @@
+use std::{collections::HashSet, fmt};
+
 use gltf::{buffer::Data, Document};
 
 use crate::{
     color::Color,
     material::Material,
-    math::{Vec2, Vec3},
-    mesh::{Mesh, Vertex},
-    texture::{Sampler, Texture, TextureStorage},
-    util::rand_color,
+    math::{Quat, Vec2, Vec3, Vec4},
+    mesh::{Mesh, Topology, Vertex},
+    scene::{Node, Scene},
+    texture::{ColorSpace, Sampler, Texture, TextureError, TextureStorage},
+    transform::Transform,
+    util::indexed_rand_color,
 };
 
-pub fn load_glft(path: &str) -> (Vec<Mesh>, TextureStorage) {
-    let (document, buffers, images) = gltf::import(path).unwrap();
+// 模型加载错误
+#[derive(Debug)]
+pub enum LoaderError {
+    // 文件读取失败
+    Io(std::io::Error),
+    // glTF解析失败
+    Parse(gltf::Error),
+    // 缺少必需的顶点属性
+    MissingAttribute(gltf::Semantic),
+    // 索引越界
+    BadIndex {
+        target: &'static str,
+        index: usize,
+        len: usize,
+    },
+    // 纹理图像无效
+    Texture {
+        index: usize,
+        error: TextureError,
+    },
+}
+impl fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "IO error: {}", err),
+            Self::Parse(err) => write!(f, "glTF parse error: {}", err),
+            Self::MissingAttribute(semantic) => {
+                write!(f, "Missing vertex attribute: {:?}", semantic)
+            }
+            Self::BadIndex { target, index, len } => {
+                write!(f, "{} index {} out of range (len {})", target, index, len)
+            }
+            Self::Texture { index, error } => write!(f, "Invalid texture {}: {}", index, error),
+        }
+    }
+}
+impl std::error::Error for LoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Texture { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<gltf::Error> for LoaderError {
+    fn from(err: gltf::Error) -> Self {
+        match err {
+            gltf::Error::Io(err) => Self::Io(err),
+            err => Self::Parse(err),
+        }
+    }
+}
+impl From<std::io::Error> for LoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
 
-    let textures = load_textures(&document, &images);
-    let meshes = load_meshes(&document, &buffers);
+pub fn load_glft(path: &str) -> Result<(Scene, TextureStorage), LoaderError> {
+    let (document, buffers, images) = gltf::import(path)?;
 
-    (
-        meshes,
+    let textures = load_textures(&document, &images)?;
+    let meshes = load_meshes(&document, &buffers)?;
+    let scene = load_scene(&document, meshes);
+
+    Ok((
+        scene,
         TextureStorage {
             texture_id_map: textures
                 .into_iter()
                 .map(|texture| (texture.id, texture))
                 .collect(),
         },
-    )
+    ))
 }
 
-pub fn load_textures(document: &Document, images: &Vec<gltf::image::Data>) -> Vec<Texture> {
+pub fn load_textures(
+    document: &Document,
+    images: &[gltf::image::Data],
+) -> Result<Vec<Texture>, LoaderError> {
+    // 被材质用作基础颜色或自发光的纹理为sRGB编码，其余为线性数据
+    let srgb_textures: HashSet<usize> = document
+        .materials()
+        .flat_map(|material| {
+            [
+                material
+                    .pbr_metallic_roughness()
+                    .base_color_texture()
+                    .map(|info| info.texture().index()),
+                material
+                    .emissive_texture()
+                    .map(|info| info.texture().index()),
+            ]
+        })
+        .flatten()
+        .collect();
+
     let mut textures = Vec::new();
     for texture in document.textures() {
         let source = texture.source();
         let sampler = texture.sampler();
-        let image = images.get(source.index()).unwrap();
-
-        let texture = Texture {
-            id: texture.index(),
-            width: image.width,
-            height: image.height,
-            format: image.format,
-            data: image.pixels.clone(),
-            sampler: Sampler {
+        let image = images.get(source.index()).ok_or(LoaderError::BadIndex {
+            target: "image",
+            index: source.index(),
+            len: images.len(),
+        })?;
+
+        let color_space = if srgb_textures.contains(&texture.index()) {
+            ColorSpace::Srgb
+        } else {
+            ColorSpace::Linear
+        };
+        let texture = Texture::new(
+            texture.index(),
+            image.width,
+            image.height,
+            image.format,
+            color_space,
+            &image.pixels,
+            Sampler {
                 mag_filter: sampler.mag_filter(),
                 min_filter: sampler.min_filter(),
                 wrap_s: sampler.wrap_s(),
                 wrap_t: sampler.wrap_t(),
             },
-        };
+        )
+        .map_err(|error| LoaderError::Texture {
+            index: texture.index(),
+            error,
+        })?;
         // println!(
         //     "Texture id: {:?}, width: {:?}, height: {:?}, format: {:?}, data len: {:?}, sampler: {:?}",
         //     texture.id,
         //     texture.width,
         //     texture.height,
         //     texture.format,
-        //     texture.data.len(),
+        //     texture.levels.len(),
         //     texture.sampler
         // );
         textures.push(texture);
     }
-    textures
+    Ok(textures)
 }
 
-pub fn load_meshes(document: &Document, buffers: &Vec<Data>) -> Vec<Mesh> {
+// 节点层级，没有场景时所有没有父节点的节点都作为根节点
+pub fn load_scene(document: &Document, meshes: Vec<Vec<Mesh>>) -> Scene {
+    // 没有节点时每个网格单独作为一个根节点
+    if document.nodes().len() == 0 {
+        let mut scene = Scene::default();
+        for (i, primitives) in meshes.into_iter().enumerate() {
+            scene.meshes.push(primitives);
+            scene.nodes.push(Node {
+                mesh: Some(i),
+                ..Default::default()
+            });
+            scene.roots.push(i);
+        }
+        return scene;
+    }
+
+    let nodes: Vec<Node> = document
+        .nodes()
+        .map(|gltf_node| {
+            let (translation, rotation, scale) = gltf_node.transform().decomposed();
+            Node {
+                name: gltf_node.name().map(String::from),
+                transform: Transform {
+                    translation: translation.into(),
+                    rotation: Quat::from_xyzw(rotation[0], rotation[1], rotation[2], rotation[3])
+                        .normalize(),
+                    scale: scale.into(),
+                },
+                children: gltf_node.children().map(|child| child.index()).collect(),
+                mesh: gltf_node.mesh().map(|gltf_mesh| gltf_mesh.index()),
+            }
+        })
+        .collect();
+
+    let roots: Vec<usize> = match document.default_scene().or(document.scenes().next()) {
+        Some(gltf_scene) => gltf_scene.nodes().map(|node| node.index()).collect(),
+        None => {
+            let mut has_parent = vec![false; nodes.len()];
+            for node in nodes.iter() {
+                for &child in node.children.iter() {
+                    has_parent[child] = true;
+                }
+            }
+            (0..nodes.len()).filter(|&i| !has_parent[i]).collect()
+        }
+    };
+
+    Scene {
+        meshes,
+        nodes,
+        roots,
+    }
+}
+
+// 按glTF网格分组返回图元（所有拓扑类型都由primitive_indices转换）
+pub fn load_meshes(document: &Document, buffers: &[Data]) -> Result<Vec<Vec<Mesh>>, LoaderError> {
     let mut meshes = Vec::new();
 
     for gltf_mesh in document.meshes() {
+        let mut primitives = Vec::new();
         for gltf_primitive in gltf_mesh.primitives() {
-            let mut mesh = Mesh::default();
+            primitives.push(load_primitive(&gltf_primitive, buffers)?);
+        }
+        meshes.push(primitives);
+    }
+    Ok(meshes)
+}
 
-            // 顶点数据
-            if gltf_primitive.mode() != gltf::mesh::Mode::Triangles {
-                println!("Primitive mode: {:?}", gltf_primitive.mode());
-                panic!("Only Triangles mode is supported");
-            }
-            let reader = gltf_primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+pub fn load_primitive(
+    gltf_primitive: &gltf::Primitive,
+    buffers: &[Data],
+) -> Result<Mesh, LoaderError> {
+    let mut mesh = Mesh::default();
 
-            let mut positions: Vec<[f32; 3]> = Vec::new();
-            let mut normals: Vec<[f32; 3]> = Vec::new();
-            let mut colors: Vec<[f32; 3]> = Vec::new();
-            let mut texcoords: Vec<[f32; 2]> = Vec::new();
+    // 顶点数据
+    let reader = gltf_primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data[..]));
 
-            for (semantic, _) in gltf_primitive.attributes() {
-                match semantic {
-                    gltf::Semantic::Positions => {
-                        positions = reader.read_positions().unwrap().collect();
-                    }
-                    gltf::Semantic::Normals => {
-                        normals = reader.read_normals().unwrap().collect();
-                    }
-                    gltf::Semantic::Colors(set) => {
-                        colors = reader.read_colors(set).unwrap().into_rgb_f32().collect();
-                    }
-                    gltf::Semantic::TexCoords(set) => {
-                        texcoords = reader.read_tex_coords(set).unwrap().into_f32().collect();
-                    }
-                    _ => {}
-                }
-            }
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 4]> = Vec::new();
+    let mut texcoords: Vec<[f32; 2]> = Vec::new();
 
-            let Some(indices) =  reader.read_indices() else {
-                continue;
-            };
-            let indices = indices.into_u32();
-
-            for index in indices {
-                let vertex_position: Vec3 = positions.get(index as usize).unwrap().clone().into();
-                let vertex_normal: Option<Vec3> =
-                    normals.get(index as usize).map(|v| v.clone().into());
-                let vertex_texcoord: Option<Vec2> =
-                    texcoords.get(index as usize).map(|v| v.clone().into());
-                let vertex_color: Option<Color> =
-                    colors.get(index as usize).map(|v| v.clone().into());
-
-                mesh.vertices.push(Vertex {
-                    position: vertex_position.extend(1.0),
-                    normal: vertex_normal,
-                    texcoord: vertex_texcoord,
-                    // 如果顶点没有颜色，就随机生成一个
-                    color: vertex_color.or(Some(rand_color())),
-                });
+    for (semantic, _) in gltf_primitive.attributes() {
+        let missing = || LoaderError::MissingAttribute(semantic.clone());
+        match semantic {
+            gltf::Semantic::Positions => {
+                positions = reader.read_positions().ok_or_else(missing)?.collect();
+            }
+            gltf::Semantic::Normals => {
+                normals = reader.read_normals().ok_or_else(missing)?.collect();
             }
+            gltf::Semantic::Colors(set) => {
+                colors = reader
+                    .read_colors(set)
+                    .ok_or_else(missing)?
+                    .into_rgba_f32()
+                    .collect();
+            }
+            // 材质纹理均使用第一套纹理坐标
+            gltf::Semantic::TexCoords(0) => {
+                texcoords = reader
+                    .read_tex_coords(0)
+                    .ok_or_else(missing)?
+                    .into_f32()
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    if positions.is_empty() {
+        return Err(LoaderError::MissingAttribute(gltf::Semantic::Positions));
+    }
+
+    // 没有索引缓冲的图元按顶点顺序生成索引
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    let (topology, indices) = primitive_indices(gltf_primitive.mode(), &indices);
+    mesh.topology = topology;
+    if let Some(&index) = indices
+        .iter()
+        .find(|&&index| index as usize >= positions.len())
+    {
+        return Err(LoaderError::BadIndex {
+            target: "vertex",
+            index: index as usize,
+            len: positions.len(),
+        });
+    }
+    mesh.indices = Some(indices);
+
+    // 每个glTF顶点只存储一次，由索引缓冲引用
+    for (index, &position) in positions.iter().enumerate() {
+        let vertex_position: Vec3 = position.into();
+        let vertex_normal: Option<Vec3> = normals.get(index).map(|&v| v.into());
+        let vertex_texcoord: Option<Vec2> = texcoords.get(index).map(|&v| v.into());
+        let vertex_color: Option<Color> = colors.get(index).map(|&v| v.into());
+
+        mesh.vertices.push(Vertex {
+            position: vertex_position.extend(1.0),
+            normal: vertex_normal,
+            texcoord: vertex_texcoord,
+            // 如果顶点没有颜色，就按顶点序号随机生成一个
+            color: vertex_color.or_else(|| Some(indexed_rand_color(index as u64))),
+        });
+    }
+
+    // 材质
+    mesh.material = load_material(&gltf_primitive.material());
+
+    Ok(mesh)
+}
+
+// glTF金属度/粗糙度材质（Blinn-Phong参数采用默认值）
+pub fn load_material(gltf_material: &gltf::Material) -> Material {
+    let pbr = gltf_material.pbr_metallic_roughness();
+    Material {
+        base_color_factor: Vec4::from(pbr.base_color_factor()),
+        base_color_texture: pbr.base_color_texture().map(|info| info.texture().index()),
+        metallic_factor: pbr.metallic_factor(),
+        roughness_factor: pbr.roughness_factor(),
+        metallic_roughness_texture: pbr
+            .metallic_roughness_texture()
+            .map(|info| info.texture().index()),
+        normal_texture: gltf_material
+            .normal_texture()
+            .map(|normal| normal.texture().index()),
+        normal_scale: gltf_material
+            .normal_texture()
+            .map_or(1.0, |normal| normal.scale()),
+        occlusion_texture: gltf_material
+            .occlusion_texture()
+            .map(|occlusion| occlusion.texture().index()),
+        occlusion_strength: gltf_material
+            .occlusion_texture()
+            .map_or(1.0, |occlusion| occlusion.strength()),
+        emissive_factor: Vec3::from(gltf_material.emissive_factor()),
+        emissive_texture: gltf_material
+            .emissive_texture()
+            .map(|info| info.texture().index()),
+        alpha_mode: gltf_material.alpha_mode(),
+        alpha_cutoff: gltf_material.alpha_cutoff().unwrap_or(0.5),
+        ..Default::default()
+    }
+}
 
-            // 材质（采用默认的blinn-phong材质）
-            let material = Material::default();
-            // let gltf_material = gltf_primitive.material();
-            mesh.material = material;
+// 将glTF图元的索引转换为三角形/线段/点列表
+pub fn primitive_indices(mode: gltf::mesh::Mode, indices: &[u32]) -> (Topology, Vec<u32>) {
+    use gltf::mesh::Mode;
 
-            meshes.push(mesh);
+    let n = indices.len();
+    match mode {
+        Mode::Triangles => (Topology::Triangles, indices[..n - n % 3].to_vec()),
+        Mode::TriangleStrip => {
+            // 奇数三角形交换后两个顶点，保持一致的环绕方向
+            let triangles = (0..n.saturating_sub(2))
+                .flat_map(|i| {
+                    if i % 2 == 0 {
+                        [indices[i], indices[i + 1], indices[i + 2]]
+                    } else {
+                        [indices[i], indices[i + 2], indices[i + 1]]
+                    }
+                })
+                .collect();
+            (Topology::Triangles, triangles)
+        }
+        Mode::TriangleFan => {
+            let triangles = (0..n.saturating_sub(2))
+                .flat_map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+                .collect();
+            (Topology::Triangles, triangles)
+        }
+        Mode::Lines => (Topology::Lines, indices[..n - n % 2].to_vec()),
+        Mode::LineStrip | Mode::LineLoop => {
+            let mut lines: Vec<u32> = (0..n.saturating_sub(1))
+                .flat_map(|i| [indices[i], indices[i + 1]])
+                .collect();
+            if mode == Mode::LineLoop && n > 2 {
+                lines.extend_from_slice(&[indices[n - 1], indices[0]]);
+            }
+            (Topology::Lines, lines)
         }
+        Mode::Points => (Topology::Points, indices.to_vec()),
     }
-    meshes
 }
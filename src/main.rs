@@ -0,0 +1,336 @@
+use std::{env, process};
+
+use tiny_renderer::{
+    camera::Camera,
+    light::{AmbientLight, DirectionalLight, Light, PointLight, SpotLight},
+    loader::load_glft,
+    math::Vec3,
+    msaa::SampleCount,
+    postprocess::{downsample, fxaa, DownsampleFilter},
+    renderer::{Projection, Renderer, RendererSettings, TransparencyMode, Viewport},
+    shader::{pbr_shader, phong_shader, FragmentShader},
+    shadow::ShadowSettings,
+};
+
+const USAGE: &str = "
+Usage: tiny-renderer <MODEL.gltf> [OPTIONS]
+
+Options:
+    -o, --output <FILE>             output image (.png/.tga/.ppm) [default: output.png]
+        --depth-output <FILE>       also write the depth buffer as a grayscale image
+        --width <N>                 image width [default: 1024]
+        --height <N>                image height [default: 720]
+        --camera-position <X,Y,Z>   [default: 1,2,3]
+        --camera-target <X,Y,Z>     [default: 0,0,0]
+        --fov <DEGREES>             vertical field of view [default: 60]
+        --near <DISTANCE>           [default: 0.1]
+        --far <DISTANCE>            [default: 1000]
+        --light-position <X,Y,Z>    [default: -5,5,5]
+        --light-intensity <I>       [default: 100]
+        --point-light <X,Y,Z,I>     add another point light (repeatable)
+        --directional-light <DX,DY,DZ,I>
+                                    add a directional light (repeatable)
+        --spot-light <X,Y,Z,DX,DY,DZ,I,INNER,OUTER>
+                                    add a spot light, cone half-angles in degrees (repeatable)
+        --ambient <I>               ambient light intensity [default: 0.2]
+        --projection <MODE>         perspective | orthographic [default: perspective]
+        --shadows                   render shadow maps (cube maps for point lights)
+        --shadow-resolution <N>     shadow map size, implies --shadows [default: 512]
+        --transparency <MODE>       sorted | oit (per-pixel linked lists) [default: sorted]
+        --msaa <N>                  samples per pixel: 1 | 2 | 4 | 8 | 16 [default: 1]
+        --ssaa <N>                  render at N times the resolution and downsample [default: 1]
+        --ssaa-filter <FILTER>      box | lanczos [default: box]
+        --fxaa                      apply FXAA to the final image
+        --parallel                  multithreaded tile rasterization (requires the `parallel` feature)
+        --wireframe                 draw triangle edges
+        --vertex-color              interpolate vertex colors when fragment shading is off
+        --shader <SHADER>           phong | pbr [default: phong]
+        --no-fragment-shading       disable the fragment shader
+    -h, --help                      print this help
+";
+
+// 命令行参数
+#[derive(Debug)]
+struct Args {
+    model: String,
+    output: String,
+    depth_output: Option<String>,
+    width: u32,
+    height: u32,
+    camera_position: Vec3,
+    camera_target: Vec3,
+    fov: f32,
+    near: f32,
+    far: f32,
+    light: PointLight,
+    // 额外的光源
+    lights: Vec<Light>,
+    ambient: f32,
+    shader: fn() -> FragmentShader,
+    settings: RendererSettings,
+    // 超采样倍数（1为不超采样）
+    supersampling: u32,
+    downsample_filter: DownsampleFilter,
+    fxaa: bool,
+}
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            output: "output.png".to_string(),
+            depth_output: None,
+            width: 1024,
+            height: 720,
+            camera_position: Vec3::new(1.0, 2.0, 3.0),
+            camera_target: Vec3::ZERO,
+            fov: 60.0,
+            near: 0.1,
+            far: 1000.0,
+            light: PointLight {
+                position: Vec3::new(-5.0, 5.0, 5.0),
+                intensity: 100.0,
+            },
+            lights: Vec::new(),
+            ambient: 0.2,
+            shader: phong_shader,
+            settings: RendererSettings {
+                fragment_shading: true,
+                ..Default::default()
+            },
+            supersampling: 1,
+            downsample_filter: DownsampleFilter::Box,
+            fxaa: false,
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut model = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-o" | "--output" => parsed.output = value()?,
+            "--depth-output" => parsed.depth_output = Some(value()?),
+            "--width" => parsed.width = parse_number(&value()?)?,
+            "--height" => parsed.height = parse_number(&value()?)?,
+            "--camera-position" => parsed.camera_position = parse_vec3(&value()?)?,
+            "--camera-target" => parsed.camera_target = parse_vec3(&value()?)?,
+            "--fov" => parsed.fov = parse_number(&value()?)?,
+            "--near" => parsed.near = parse_number(&value()?)?,
+            "--far" => parsed.far = parse_number(&value()?)?,
+            "--light-position" => parsed.light.position = parse_vec3(&value()?)?,
+            "--light-intensity" => parsed.light.intensity = parse_number(&value()?)?,
+            "--point-light" => {
+                let [x, y, z, intensity] = parse_floats(&value()?)?;
+                parsed.lights.push(Light::Point(PointLight {
+                    position: Vec3::new(x, y, z),
+                    intensity,
+                }));
+            }
+            "--directional-light" => {
+                let [dx, dy, dz, intensity] = parse_floats(&value()?)?;
+                parsed.lights.push(Light::Directional(DirectionalLight {
+                    direction: Vec3::new(dx, dy, dz).normalize(),
+                    intensity,
+                }));
+            }
+            "--spot-light" => {
+                let [x, y, z, dx, dy, dz, intensity, inner, outer] = parse_floats(&value()?)?;
+                parsed.lights.push(Light::Spot(SpotLight {
+                    position: Vec3::new(x, y, z),
+                    direction: Vec3::new(dx, dy, dz).normalize(),
+                    intensity,
+                    inner_cone_angle: inner.to_radians(),
+                    outer_cone_angle: outer.to_radians(),
+                }));
+            }
+            "--ambient" => parsed.ambient = parse_number(&value()?)?,
+            "--projection" => {
+                parsed.settings.projection = match value()?.as_str() {
+                    "perspective" => Projection::Perspective,
+                    "orthographic" => Projection::Orthographic,
+                    other => return Err(format!("Unknown projection: {}", other)),
+                }
+            }
+            "--shader" => {
+                parsed.shader = match value()?.as_str() {
+                    "phong" => phong_shader,
+                    "pbr" => pbr_shader,
+                    other => return Err(format!("Unknown shader: {}", other)),
+                }
+            }
+            "--shadows" => {
+                parsed
+                    .settings
+                    .shadow
+                    .get_or_insert_with(ShadowSettings::default);
+            }
+            "--shadow-resolution" => {
+                let resolution: u32 = parse_number(&value()?)?;
+                parsed
+                    .settings
+                    .shadow
+                    .get_or_insert_with(ShadowSettings::default)
+                    .resolution = resolution.max(1);
+            }
+            "--transparency" => {
+                parsed.settings.transparency = match value()?.as_str() {
+                    "sorted" => TransparencyMode::Sorted,
+                    "oit" => TransparencyMode::LinkedList,
+                    other => return Err(format!("Unknown transparency mode: {}", other)),
+                }
+            }
+            "--msaa" => {
+                let count: u32 = parse_number(&value()?)?;
+                parsed.settings.msaa = SampleCount::from_count(count)
+                    .ok_or_else(|| format!("Unsupported MSAA sample count: {}", count))?;
+            }
+            "--ssaa" => {
+                let factor: u32 = parse_number(&value()?)?;
+                parsed.supersampling = factor.max(1);
+            }
+            "--ssaa-filter" => {
+                parsed.downsample_filter = match value()?.as_str() {
+                    "box" => DownsampleFilter::Box,
+                    "lanczos" => DownsampleFilter::Lanczos,
+                    other => return Err(format!("Unknown downsample filter: {}", other)),
+                }
+            }
+            "--fxaa" => parsed.fxaa = true,
+            "--parallel" => {
+                if !cfg!(feature = "parallel") {
+                    return Err("--parallel requires the `parallel` feature".to_string());
+                }
+                parsed.settings.parallel = true;
+            }
+            "--wireframe" => parsed.settings.wireframe = true,
+            "--vertex-color" => parsed.settings.vertex_color_interp = true,
+            "--no-fragment-shading" => parsed.settings.fragment_shading = false,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ => {
+                if model.replace(arg.clone()).is_some() {
+                    return Err(format!("Unexpected argument: {}", arg));
+                }
+            }
+        }
+    }
+    parsed.model = model.ok_or("Missing model path")?;
+    if parsed.width == 0 || parsed.height == 0 {
+        return Err("Image size must be positive".to_string());
+    }
+    // 超采样后的宽、高和像素数都不能溢出
+    let factor = parsed.supersampling;
+    if parsed
+        .width
+        .checked_mul(factor)
+        .zip(parsed.height.checked_mul(factor))
+        .and_then(|(width, height)| width.checked_mul(height))
+        .is_none()
+    {
+        return Err(format!(
+            "Image size {}x{} is too large for --ssaa {}",
+            parsed.width, parsed.height, factor
+        ));
+    }
+    if parsed.near <= 0.0 || parsed.far <= parsed.near {
+        return Err("Expected 0 < near < far".to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number: {}", value))
+}
+
+// 逗号分隔的N个数
+fn parse_floats<const N: usize>(value: &str) -> Result<[f32; N], String> {
+    let components = value
+        .split(',')
+        .map(|v| parse_number::<f32>(v.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    components
+        .try_into()
+        .map_err(|_| format!("Expected {} comma separated numbers but got: {}", N, value))
+}
+
+fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    let [x, y, z] = parse_floats(value)?;
+    Ok(Vec3::new(x, y, z))
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let (scene, texture_storage) = match load_glft(&args.model) {
+        Ok(model) => model,
+        Err(err) => {
+            eprintln!("error: failed to load {}: {}", args.model, err);
+            process::exit(1);
+        }
+    };
+
+    let mut camera = Camera::new(
+        args.near,
+        args.far,
+        args.width as f32 / args.height as f32,
+        args.fov.to_radians(),
+        args.camera_position,
+    );
+    camera.look_at(args.camera_target, Vec3::Y);
+
+    // 超采样时以更高的分辨率渲染，点的大小同样放大
+    let factor = args.supersampling;
+    let viewport = Viewport::new(0, 0, args.width * factor, args.height * factor);
+    let settings = RendererSettings {
+        point_size: args.settings.point_size * factor as f32,
+        ..args.settings
+    };
+    let mut renderer = Renderer::new(camera, viewport, settings);
+    renderer.fragment_shader = Some((args.shader)());
+
+    let mut lights = vec![
+        Light::Ambient(AmbientLight {
+            intensity: args.ambient,
+        }),
+        Light::Point(args.light),
+    ];
+    lights.extend(args.lights.iter().copied());
+    renderer.draw_scene(&scene, &lights, &texture_storage);
+
+    let mut color = downsample(
+        &renderer.color_frame_buffer(),
+        factor,
+        args.downsample_filter,
+    );
+    if args.fxaa {
+        color = fxaa(&color);
+    }
+    if let Err(err) = color.save(&args.output) {
+        eprintln!("error: failed to write {}: {}", args.output, err);
+        process::exit(1);
+    }
+    if let Some(depth_output) = &args.depth_output {
+        let depth = downsample(
+            &renderer.depth_frame_buffer(),
+            factor,
+            DownsampleFilter::Box,
+        );
+        if let Err(err) = depth.save(depth_output) {
+            eprintln!("error: failed to write {}: {}", depth_output, err);
+            process::exit(1);
+        }
+    }
+}
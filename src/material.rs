@@ -1,6 +1,8 @@
-use crate::math::Vec3;
+use gltf::material::AlphaMode;
 
-// Blinn-Phong 材质
+use crate::math::{Vec3, Vec4};
+
+// 材质：Blinn-Phong参数和glTF金属度/粗糙度（PBR）参数
 #[derive(Clone, Copy, Debug)]
 pub struct Material {
     // 环境光反射系数Ka
@@ -11,6 +13,29 @@ pub struct Material {
     pub specular: Vec3,
     // 镜面反射高光度p
     pub shininess: f32,
+
+    // 基础颜色系数（RGBA），与基础颜色纹理相乘
+    pub base_color_factor: Vec4,
+    // 以下纹理均为TextureStorage中的纹理id
+    pub base_color_texture: Option<usize>,
+    // 金属度系数
+    pub metallic_factor: f32,
+    // 粗糙度系数
+    pub roughness_factor: f32,
+    // 金属度/粗糙度纹理（B通道为金属度，G通道为粗糙度）
+    pub metallic_roughness_texture: Option<usize>,
+    // 切线空间法线纹理
+    pub normal_texture: Option<usize>,
+    pub normal_scale: f32,
+    // 环境光遮蔽纹理（R通道）
+    pub occlusion_texture: Option<usize>,
+    pub occlusion_strength: f32,
+    // 自发光
+    pub emissive_factor: Vec3,
+    pub emissive_texture: Option<usize>,
+    // 透明度模式：Opaque忽略透明度，Mask按alpha_cutoff丢弃片段，Blend在透明通道中混合
+    pub alpha_mode: AlphaMode,
+    pub alpha_cutoff: f32,
 }
 
 impl Default for Material {
@@ -20,6 +45,19 @@ impl Default for Material {
             diffuse: Vec3::new(0.14, 0.24, 0.34),
             specular: Vec3::new(0.5, 0.5, 0.5),
             shininess: 64.0,
+            base_color_factor: Vec4::ONE,
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_factor: Vec3::ZERO,
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: 0.5,
         }
     }
 }
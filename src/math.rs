@@ -45,6 +45,10 @@ impl Vec2 {
     pub fn extend(self, z: f32) -> Vec3 {
         Vec3::new(self.x, self.y, z)
     }
+    // 线性插值
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
 }
 impl Add<Vec2> for Vec2 {
     type Output = Self;
@@ -148,6 +152,10 @@ impl Vec3 {
     pub fn truncate(self) -> Vec2 {
         Vec2::new(self.x, self.y)
     }
+    // 线性插值
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
 }
 impl Add<Vec3> for Vec3 {
     type Output = Self;
@@ -179,6 +187,17 @@ impl Mul<f32> for Vec3 {
         }
     }
 }
+// 逐分量相乘
+impl Mul<Vec3> for Vec3 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
 impl Neg for Vec3 {
     type Output = Self;
     fn neg(self) -> Self {
@@ -267,6 +286,10 @@ impl Vec4 {
     pub fn truncate(self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
     }
+    // 线性插值
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        self + (rhs - self) * t
+    }
 }
 impl Add<Vec4> for Vec4 {
     type Output = Self;
@@ -308,6 +331,7 @@ impl From<[f32; 4]> for Vec4 {
 }
 
 //// 2x2按列存储矩阵
+#[derive(Clone, Copy, Debug)]
 pub struct Mat2 {
     pub x_axis: Vec2,
     pub y_axis: Vec2,
@@ -333,9 +357,25 @@ impl Mat2 {
             },
         }
     }
-    // TODO 逆矩阵
+    // 行列式
+    pub fn determinant(self) -> f32 {
+        self.x_axis.x * self.y_axis.y - self.y_axis.x * self.x_axis.y
+    }
+    // 逆矩阵，行列式为0（奇异矩阵）时返回None
+    pub fn try_inverse(self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv_det = det.recip();
+        Some(Self::from_cols(
+            Vec2::new(self.y_axis.y, -self.x_axis.y) * inv_det,
+            Vec2::new(-self.y_axis.x, self.x_axis.x) * inv_det,
+        ))
+    }
+    // 逆矩阵（矩阵必须可逆）
     pub fn inverse(self) -> Self {
-        todo!()
+        self.try_inverse().expect("singular matrix has no inverse")
     }
 }
 impl Add<Mat2> for Mat2 {
@@ -394,6 +434,14 @@ impl Mat3 {
             z_axis,
         }
     }
+    // 4x4矩阵左上角的3x3部分（线性变换部分）
+    pub fn from_mat4(mat: Mat4) -> Self {
+        Self::from_cols(
+            mat.x_axis.truncate(),
+            mat.y_axis.truncate(),
+            mat.z_axis.truncate(),
+        )
+    }
     // 转置
     pub fn transpose(self) -> Self {
         Self {
@@ -414,9 +462,28 @@ impl Mat3 {
             },
         }
     }
-    // TODO 逆矩阵
+    // 行列式（三列的混合积）
+    pub fn determinant(self) -> f32 {
+        self.x_axis.dot(self.y_axis.cross(self.z_axis))
+    }
+    // 逆矩阵，行列式为0（奇异矩阵）时返回None
+    pub fn try_inverse(self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        // 伴随矩阵的每一行为两列的叉乘
+        let adjugate = Self::from_cols(
+            self.y_axis.cross(self.z_axis),
+            self.z_axis.cross(self.x_axis),
+            self.x_axis.cross(self.y_axis),
+        )
+        .transpose();
+        Some(adjugate * det.recip())
+    }
+    // 逆矩阵（矩阵必须可逆）
     pub fn inverse(self) -> Self {
-        todo!()
+        self.try_inverse().expect("singular matrix has no inverse")
     }
 }
 impl Add<Mat3> for Mat3 {
@@ -529,9 +596,102 @@ impl Mat4 {
             },
         }
     }
-    // TODO 逆矩阵
+    // 行列式
+    pub fn determinant(self) -> f32 {
+        let (s, t, u, v) = self.cross_terms();
+        s.dot(v) + t.dot(u)
+    }
+    // 逆矩阵，行列式为0（奇异矩阵）时返回None
+    // 参考 Eric Lengyel, Foundations of Game Engine Development, Vol. 1
+    pub fn try_inverse(self) -> Option<Self> {
+        let (s, t, u, v) = self.cross_terms();
+        let det = s.dot(v) + t.dot(u);
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv_det = det.recip();
+        let (s, t, u, v) = (s * inv_det, t * inv_det, u * inv_det, v * inv_det);
+        let (a, b, c, d) = (
+            self.x_axis.truncate(),
+            self.y_axis.truncate(),
+            self.z_axis.truncate(),
+            self.w_axis.truncate(),
+        );
+        let (x, y, z, w) = (self.x_axis.w, self.y_axis.w, self.z_axis.w, self.w_axis.w);
+        // 逆矩阵的四行
+        let r0 = (b.cross(v) + t * y).extend(-b.dot(t));
+        let r1 = (v.cross(a) - t * x).extend(a.dot(t));
+        let r2 = (d.cross(u) + s * w).extend(-d.dot(s));
+        let r3 = (u.cross(c) - s * z).extend(c.dot(s));
+        Some(Self::from_cols(r0, r1, r2, r3).transpose())
+    }
+    // 逆矩阵（矩阵必须可逆）
     pub fn inverse(self) -> Self {
-        todo!()
+        self.try_inverse().expect("singular matrix has no inverse")
+    }
+    // 行列式和逆矩阵共用的中间量
+    fn cross_terms(self) -> (Vec3, Vec3, Vec3, Vec3) {
+        let (a, b, c, d) = (
+            self.x_axis.truncate(),
+            self.y_axis.truncate(),
+            self.z_axis.truncate(),
+            self.w_axis.truncate(),
+        );
+        let (x, y, z, w) = (self.x_axis.w, self.y_axis.w, self.z_axis.w, self.w_axis.w);
+        (a.cross(b), c.cross(d), a * y - b * x, c * w - d * z)
+    }
+
+    // 视图变换矩阵：相机位于eye，朝向-z看向target
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Self {
+        Self::look_to(eye, target - eye, up)
+    }
+    pub fn look_to(eye: Vec3, direction: Vec3, up: Vec3) -> Self {
+        let back = -direction.normalize();
+        let right = up.cross(back).normalize();
+        let up = back.cross(right);
+        Self::from_cols(
+            Vec4::new(right.x, up.x, back.x, 0.0),
+            Vec4::new(right.y, up.y, back.y, 0.0),
+            Vec4::new(right.z, up.z, back.z, 0.0),
+            Vec4::new(-right.dot(eye), -up.dot(eye), -back.dot(eye), 1.0),
+        )
+    }
+
+    // 透视投影矩阵（与Frustum::persp_projection_transformation相同：
+    // 近平面映射到z = 1，远平面映射到z = -1，w为视图空间的z）
+    // fov为垂直视野（弧度），near、far为到相机的距离
+    #[rustfmt::skip]
+    pub fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Self {
+        assert!(near > 0.0 && far > near);
+        let f = (fov / 2.0).tan().recip();
+        Self::from_rows_slice(&[
+            -f / aspect, 0., 0., 0.,
+            0., -f, 0., 0.,
+            0., 0., -(near + far) / (far - near), -2. * near * far / (far - near),
+            0., 0., 1., 0.,
+        ])
+    }
+    // 远平面在无穷远处的透视投影矩阵（far趋于无穷时的极限）
+    #[rustfmt::skip]
+    pub fn perspective_infinite(fov: f32, aspect: f32, near: f32) -> Self {
+        assert!(near > 0.0);
+        let f = (fov / 2.0).tan().recip();
+        Self::from_rows_slice(&[
+            -f / aspect, 0., 0., 0.,
+            0., -f, 0., 0.,
+            0., 0., -1., -2. * near,
+            0., 0., 1., 0.,
+        ])
+    }
+    // 正交投影矩阵，将视图空间中[left, right] x [bottom, top] x [-far, -near]映射到NDC
+    #[rustfmt::skip]
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        Self::from_rows_slice(&[
+            2. / (right - left), 0., 0., -(right + left) / (right - left),
+            0., 2. / (top - bottom), 0., -(top + bottom) / (top - bottom),
+            0., 0., 2. / (far - near), (far + near) / (far - near),
+            0., 0., 0., 1.,
+        ])
     }
 }
 impl Add<Mat4> for Mat4 {
@@ -724,6 +884,70 @@ impl Quat {
             Vec4::W,
         )
     }
+    // 由欧拉角构造（弧度），先绕x轴，再绕y轴，最后绕z轴旋转（均为固定的世界坐标轴）
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        let (sx, cx) = (x * 0.5).sin_cos();
+        let (sy, cy) = (y * 0.5).sin_cos();
+        let (sz, cz) = (z * 0.5).sin_cos();
+        Self::from_xyzw(
+            sx * cy * cz - cx * sy * sz,
+            cx * sy * cz + sx * cy * sz,
+            cx * cy * sz - sx * sy * cz,
+            cx * cy * cz + sx * sy * sz,
+        )
+    }
+    // 转换为欧拉角(x, y, z)，与from_euler互逆，y的范围为[-π/2, π/2]
+    pub fn to_euler(self) -> (f32, f32, f32) {
+        let (x, y, z, w) = (self.x, self.y, self.z, self.w);
+        let angle_x = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        let angle_y = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let angle_z = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+        (angle_x, angle_y, angle_z)
+    }
+    // 将单位向量from旋转到单位向量to的最短旋转
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Self {
+        assert!(from.is_normalized() && to.is_normalized());
+        let dot = from.dot(to);
+        if dot < -1.0 + 1e-6 {
+            // 方向相反时绕任意一个垂直轴旋转180°
+            let axis = if from.x.abs() < 0.9 {
+                Vec3::X.cross(from)
+            } else {
+                Vec3::Y.cross(from)
+            };
+            return Self::from_axis_angle(axis.normalize(), std::f32::consts::PI);
+        }
+        let axis = from.cross(to);
+        Self::from_xyzw(axis.x, axis.y, axis.z, 1.0 + dot).normalize()
+    }
+    // 点乘
+    pub fn dot(self, rhs: Self) -> f32 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+    // 归一化线性插值（沿较短的路径）
+    pub fn nlerp(self, end: Self, t: f32) -> Self {
+        let end = if self.dot(end) < 0.0 { end * -1.0 } else { end };
+        (self * (1.0 - t) + end * t).normalize()
+    }
+    // 球面线性插值（沿较短的路径，角速度恒定）
+    pub fn slerp(self, end: Self, t: f32) -> Self {
+        let mut dot = self.dot(end);
+        let end = if dot < 0.0 {
+            dot = -dot;
+            end * -1.0
+        } else {
+            end
+        };
+        // 夹角很小时退化为nlerp，避免除以sin(θ) ≈ 0
+        if dot > 1.0 - 1e-6 {
+            return self.nlerp(end, t);
+        }
+        let theta = dot.clamp(-1.0, 1.0).acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+        self * a + end * b
+    }
     pub fn length(self) -> f32 {
         Vec4::new(self.x, self.y, self.z, self.w).length()
     }
@@ -733,6 +957,10 @@ impl Quat {
     pub fn is_normalized(self) -> bool {
         Vec4::new(self.x, self.y, self.z, self.w).is_normalized()
     }
+    // 归一化
+    pub fn normalize(self) -> Self {
+        Self::from_vec4(Vec4::new(self.x, self.y, self.z, self.w).normalize())
+    }
 }
 impl Add<Quat> for Quat {
     type Output = Self;
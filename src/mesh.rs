@@ -15,9 +15,52 @@ pub struct Vertex {
     // 顶点颜色
     pub color: Option<Color>,
 }
+impl Vertex {
+    // 对顶点的所有属性进行线性插值（用于裁剪时生成新顶点）
+    pub fn lerp(&self, rhs: &Vertex, t: f32) -> Vertex {
+        Vertex {
+            position: self.position.lerp(rhs.position, t),
+            normal: self.normal.zip(rhs.normal).map(|(a, b)| a.lerp(b, t)),
+            texcoord: self.texcoord.zip(rhs.texcoord).map(|(a, b)| a.lerp(b, t)),
+            color: self.color.zip(rhs.color).map(|(a, b)| a.lerp(b, t)),
+        }
+    }
+}
+// 图元拓扑类型
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    // 每3个顶点构成一个三角形
+    #[default]
+    Triangles,
+    // 每2个顶点构成一条线段
+    Lines,
+    // 每个顶点为一个点
+    Points,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Mesh {
-    // 顶点数据（拓扑类型为Triangles）
+    // 顶点数据（共享的顶点只存储一次）
     pub vertices: Vec<Vertex>,
+    // 索引缓冲（按拓扑类型排列），None表示直接按顺序使用vertices
+    pub indices: Option<Vec<u32>>,
+    pub topology: Topology,
     pub material: Material,
 }
+impl Mesh {
+    // 索引的个数（没有索引缓冲时为顶点个数）
+    pub fn index_count(&self) -> usize {
+        match &self.indices {
+            Some(indices) => indices.len(),
+            None => self.vertices.len(),
+        }
+    }
+
+    // 第i个索引对应的顶点在vertices中的序号
+    pub fn index(&self, i: usize) -> usize {
+        match &self.indices {
+            Some(indices) => indices[i] as usize,
+            None => i,
+        }
+    }
+}
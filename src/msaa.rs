@@ -0,0 +1,79 @@
+use crate::math::Vec2;
+
+// 多重采样抗锯齿（MSAA）的每像素采样数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SampleCount {
+    // 不进行多重采样，只在像素中心采样
+    #[default]
+    X1,
+    X2,
+    X4,
+    X8,
+    X16,
+}
+impl SampleCount {
+    pub const MAX: usize = 16;
+
+    pub fn from_count(count: u32) -> Option<Self> {
+        match count {
+            1 => Some(Self::X1),
+            2 => Some(Self::X2),
+            4 => Some(Self::X4),
+            8 => Some(Self::X8),
+            16 => Some(Self::X16),
+            _ => None,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.positions().len()
+    }
+
+    // 采样点相对像素中心的偏移（标准采样模式，单位为像素）
+    pub fn positions(&self) -> &'static [Vec2] {
+        match self {
+            SampleCount::X1 => &X1_POSITIONS,
+            SampleCount::X2 => &X2_POSITIONS,
+            SampleCount::X4 => &X4_POSITIONS,
+            SampleCount::X8 => &X8_POSITIONS,
+            SampleCount::X16 => &X16_POSITIONS,
+        }
+    }
+}
+
+// 以1/16像素为单位的偏移
+const fn offset(x: i32, y: i32) -> Vec2 {
+    Vec2::new(x as f32 / 16.0, y as f32 / 16.0)
+}
+
+const X1_POSITIONS: [Vec2; 1] = [offset(0, 0)];
+const X2_POSITIONS: [Vec2; 2] = [offset(4, 4), offset(-4, -4)];
+const X4_POSITIONS: [Vec2; 4] = [offset(-2, -6), offset(6, -2), offset(-6, 2), offset(2, 6)];
+const X8_POSITIONS: [Vec2; 8] = [
+    offset(1, -3),
+    offset(-1, 3),
+    offset(5, 1),
+    offset(-3, -5),
+    offset(-5, 5),
+    offset(-7, -1),
+    offset(3, 7),
+    offset(7, -7),
+];
+const X16_POSITIONS: [Vec2; 16] = [
+    offset(1, 1),
+    offset(-1, -3),
+    offset(-3, 2),
+    offset(4, -1),
+    offset(-5, -2),
+    offset(2, 5),
+    offset(5, 3),
+    offset(3, -5),
+    offset(-2, 6),
+    offset(0, -7),
+    offset(-4, -6),
+    offset(-6, 4),
+    offset(-8, 0),
+    offset(7, -4),
+    offset(6, 7),
+    offset(-7, -8),
+];
@@ -0,0 +1,70 @@
+use crate::color::Color;
+
+// 半透明片段
+#[derive(Debug, Clone, Copy)]
+pub struct Fragment {
+    pub color: Color,
+    // 与深度缓冲相同，值越大越近
+    pub depth: f32,
+    // 覆盖的采样点（第i位对应第i个采样点）
+    pub coverage: u32,
+}
+
+// 链表节点
+#[derive(Debug, Clone, Copy)]
+pub struct FragmentNode {
+    pub fragment: Fragment,
+    // 同一像素的下一个节点
+    pub next: Option<usize>,
+}
+
+// 逐像素链表，用于顺序无关透明（OIT）
+// 所有节点存放在同一个数组中，heads记录每个像素最后插入的节点
+#[derive(Debug, Clone, Default)]
+pub struct FragmentLists {
+    pub heads: Vec<Option<usize>>,
+    pub nodes: Vec<FragmentNode>,
+}
+impl FragmentLists {
+    pub fn new(pixel_count: usize) -> Self {
+        Self {
+            heads: vec![None; pixel_count],
+            nodes: Vec::new(),
+        }
+    }
+
+    // 在第index个像素的链表头部插入片段
+    pub fn insert(&mut self, index: usize, fragment: Fragment) {
+        self.nodes.push(FragmentNode {
+            fragment,
+            next: self.heads[index],
+        });
+        self.heads[index] = Some(self.nodes.len() - 1);
+    }
+
+    // 第index个像素的所有片段（按插入顺序的逆序）
+    pub fn fragments(&self, index: usize) -> impl Iterator<Item = Fragment> + '_ {
+        let mut current = self.heads[index];
+        std::iter::from_fn(move || {
+            let node = self.nodes[current?];
+            current = node.next;
+            Some(node.fragment)
+        })
+    }
+
+    // 第index个像素的片段，从远到近排序
+    pub fn sorted_fragments(&self, index: usize) -> Vec<Fragment> {
+        let mut fragments: Vec<Fragment> = self.fragments(index).collect();
+        fragments.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+        fragments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heads.fill(None);
+        self.nodes.clear();
+    }
+}
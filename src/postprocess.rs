@@ -0,0 +1,314 @@
+use crate::{
+    framebuffer::FrameBuffer,
+    math::{Vec2, Vec4},
+};
+
+// 超采样下采样的滤波器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DownsampleFilter {
+    // 对factor x factor块内的像素取平均
+    #[default]
+    Box,
+    // Lanczos-3（可分离，更锐利，边缘可能有轻微振铃）
+    Lanczos,
+}
+
+// 超采样：将以factor倍分辨率渲染的帧缓冲缩小为原分辨率
+pub fn downsample(frame: &FrameBuffer, factor: u32, filter: DownsampleFilter) -> FrameBuffer {
+    assert!(factor > 0);
+    let width = (frame.width / factor).max(1);
+    let height = (frame.height / factor).max(1);
+    if factor == 1 {
+        return frame.clone();
+    }
+    let image = Image::from_frame_buffer(frame);
+    let pixels = match filter {
+        DownsampleFilter::Box => box_downsample(&image, factor, width, height),
+        DownsampleFilter::Lanczos => {
+            // 先水平后竖直
+            let horizontal = lanczos_downsample(&image, factor, width, true);
+            lanczos_downsample(&horizontal, factor, height, false).pixels
+        }
+    };
+    Image {
+        width,
+        height,
+        pixels,
+    }
+    .to_frame_buffer()
+}
+
+fn box_downsample(image: &Image, factor: u32, width: u32, height: u32) -> Vec<Vec4> {
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = Vec4::ZERO;
+            for dy in 0..factor {
+                for dx in 0..factor {
+                    sum = sum + image.pixel((x * factor + dx) as i32, (y * factor + dy) as i32);
+                }
+            }
+            pixels.push(sum * (1.0 / (factor * factor) as f32));
+        }
+    }
+    pixels
+}
+
+const LANCZOS_RADIUS: f32 = 3.0;
+
+// Lanczos核：sinc(x) * sinc(x / a)，|x| < a
+fn lanczos(x: f32) -> f32 {
+    if x == 0.0 {
+        return 1.0;
+    }
+    if x.abs() >= LANCZOS_RADIUS {
+        return 0.0;
+    }
+    let pi_x = std::f32::consts::PI * x;
+    LANCZOS_RADIUS * pi_x.sin() * (pi_x / LANCZOS_RADIUS).sin() / (pi_x * pi_x)
+}
+
+// 沿一个方向缩小为size个像素，核宽度按factor放大
+fn lanczos_downsample(image: &Image, factor: u32, size: u32, horizontal: bool) -> Image {
+    let (width, height) = if horizontal {
+        (size, image.height)
+    } else {
+        (image.width, size)
+    };
+    let scale = factor as f32;
+    let radius = (LANCZOS_RADIUS * scale).ceil() as i32;
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let i = if horizontal { x } else { y };
+            // 输出像素中心在原图中的位置
+            let center = (i as f32 + 0.5) * scale;
+            let first = center.floor() as i32;
+            let mut sum = Vec4::ZERO;
+            let mut weight_sum = 0.0;
+            for j in first - radius..=first + radius {
+                let weight = lanczos((j as f32 + 0.5 - center) / scale);
+                if weight == 0.0 {
+                    continue;
+                }
+                let pixel = if horizontal {
+                    image.pixel(j, y as i32)
+                } else {
+                    image.pixel(x as i32, j)
+                };
+                sum = sum + pixel * weight;
+                weight_sum += weight;
+            }
+            pixels.push(sum * (1.0 / weight_sum));
+        }
+    }
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+// FXAA参数
+// 局部亮度对比度低于max(EDGE_THRESHOLD_MIN, 最大亮度 * EDGE_THRESHOLD)时不处理
+const EDGE_THRESHOLD: f32 = 0.125;
+const EDGE_THRESHOLD_MIN: f32 = 0.0312;
+// 亚像素混合强度
+const SUBPIXEL_QUALITY: f32 = 0.75;
+// 沿边缘搜索的步长
+const SEARCH_STEPS: [f32; 12] = [1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 2.0, 2.0, 2.0, 2.0, 4.0, 8.0];
+
+// 快速近似抗锯齿（FXAA 3.11），对最终的帧缓冲做后处理
+pub fn fxaa(frame: &FrameBuffer) -> FrameBuffer {
+    let image = Image::from_frame_buffer(frame);
+    let lumas: Vec<f32> = image.pixels.iter().map(|p| luma(*p)).collect();
+    let luma_at = |x: i32, y: i32| {
+        let x = x.clamp(0, image.width as i32 - 1);
+        let y = y.clamp(0, image.height as i32 - 1);
+        lumas[(y * image.width as i32 + x) as usize]
+    };
+
+    let mut pixels = Vec::with_capacity(image.pixels.len());
+    for y in 0..image.height as i32 {
+        for x in 0..image.width as i32 {
+            let luma_m = luma_at(x, y);
+            let luma_n = luma_at(x, y + 1);
+            let luma_s = luma_at(x, y - 1);
+            let luma_e = luma_at(x + 1, y);
+            let luma_w = luma_at(x - 1, y);
+
+            // 对比度低的区域不是边缘
+            let luma_min = luma_m.min(luma_n).min(luma_s).min(luma_e).min(luma_w);
+            let luma_max = luma_m.max(luma_n).max(luma_s).max(luma_e).max(luma_w);
+            let range = luma_max - luma_min;
+            if range < EDGE_THRESHOLD_MIN.max(luma_max * EDGE_THRESHOLD) {
+                pixels.push(image.pixel(x, y));
+                continue;
+            }
+
+            let luma_ne = luma_at(x + 1, y + 1);
+            let luma_nw = luma_at(x - 1, y + 1);
+            let luma_se = luma_at(x + 1, y - 1);
+            let luma_sw = luma_at(x - 1, y - 1);
+            let luma_ns = luma_n + luma_s;
+            let luma_we = luma_w + luma_e;
+            let luma_west_corners = luma_nw + luma_sw;
+            let luma_east_corners = luma_ne + luma_se;
+            let luma_north_corners = luma_nw + luma_ne;
+            let luma_south_corners = luma_sw + luma_se;
+
+            // 边缘方向：水平边缘在竖直方向上的亮度变化更大
+            let edge_horizontal = (luma_west_corners - 2.0 * luma_w).abs()
+                + (luma_ns - 2.0 * luma_m).abs() * 2.0
+                + (luma_east_corners - 2.0 * luma_e).abs();
+            let edge_vertical = (luma_north_corners - 2.0 * luma_n).abs()
+                + (luma_we - 2.0 * luma_m).abs() * 2.0
+                + (luma_south_corners - 2.0 * luma_s).abs();
+            let horizontal = edge_horizontal >= edge_vertical;
+
+            // 选择边缘另一侧（梯度更大）的方向
+            let (luma1, luma2) = if horizontal {
+                (luma_s, luma_n)
+            } else {
+                (luma_w, luma_e)
+            };
+            let gradient1 = luma1 - luma_m;
+            let gradient2 = luma2 - luma_m;
+            let steepest1 = gradient1.abs() >= gradient2.abs();
+            let gradient_scaled = 0.25 * gradient1.abs().max(gradient2.abs());
+            let (step, luma_local_average) = if steepest1 {
+                (-1.0, 0.5 * (luma1 + luma_m))
+            } else {
+                (1.0, 0.5 * (luma2 + luma_m))
+            };
+
+            // 从像素中心移动到两像素之间的边缘上，沿边缘向两端搜索
+            let center = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let (edge, offset) = if horizontal {
+                (center + Vec2::new(0.0, step * 0.5), Vec2::X)
+            } else {
+                (center + Vec2::new(step * 0.5, 0.0), Vec2::Y)
+            };
+            let end_luma = |p: Vec2| luma(image.sample(p)) - luma_local_average;
+            let mut p1 = edge - offset;
+            let mut p2 = edge + offset;
+            let mut luma_end1 = end_luma(p1);
+            let mut luma_end2 = end_luma(p2);
+            let mut reached1 = luma_end1.abs() >= gradient_scaled;
+            let mut reached2 = luma_end2.abs() >= gradient_scaled;
+            for quality in SEARCH_STEPS.iter().skip(1) {
+                if reached1 && reached2 {
+                    break;
+                }
+                if !reached1 {
+                    p1 = p1 - offset * *quality;
+                    luma_end1 = end_luma(p1);
+                    reached1 = luma_end1.abs() >= gradient_scaled;
+                }
+                if !reached2 {
+                    p2 = p2 + offset * *quality;
+                    luma_end2 = end_luma(p2);
+                    reached2 = luma_end2.abs() >= gradient_scaled;
+                }
+            }
+
+            // 离较近的一端越近，越靠近边缘的另一侧采样
+            let (distance1, distance2) = if horizontal {
+                (center.x - p1.x, p2.x - center.x)
+            } else {
+                (center.y - p1.y, p2.y - center.y)
+            };
+            let (distance, luma_end) = if distance1 < distance2 {
+                (distance1, luma_end1)
+            } else {
+                (distance2, luma_end2)
+            };
+            let pixel_offset = 0.5 - distance / (distance1 + distance2);
+            // 端点的亮度变化方向与中心一致时才偏移
+            let correct_variation = (luma_end < 0.0) != (luma_m < luma_local_average);
+            let edge_offset = if correct_variation { pixel_offset } else { 0.0 };
+
+            // 亚像素抗锯齿：3x3邻域平均亮度与中心相差越大，偏移越大
+            let luma_average =
+                (2.0 * (luma_ns + luma_we) + luma_west_corners + luma_east_corners) / 12.0;
+            let subpixel = ((luma_average - luma_m).abs() / range).clamp(0.0, 1.0);
+            let subpixel = (-2.0 * subpixel + 3.0) * subpixel * subpixel;
+            let subpixel_offset = subpixel * subpixel * SUBPIXEL_QUALITY;
+
+            let final_offset = edge_offset.max(subpixel_offset) * step;
+            let p = if horizontal {
+                center + Vec2::new(0.0, final_offset)
+            } else {
+                center + Vec2::new(final_offset, 0.0)
+            };
+            pixels.push(image.sample(p));
+        }
+    }
+    Image {
+        width: image.width,
+        height: image.height,
+        pixels,
+    }
+    .to_frame_buffer()
+}
+
+// 感知亮度
+fn luma(color: Vec4) -> f32 {
+    0.299 * color.x + 0.587 * color.y + 0.114 * color.z
+}
+
+// 浮点RGBA图像（与FrameBuffer相同的行顺序）
+struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Vec4>,
+}
+impl Image {
+    fn from_frame_buffer(frame: &FrameBuffer) -> Self {
+        let pixels = frame
+            .data
+            .chunks_exact(4)
+            .map(|rgba| {
+                Vec4::new(
+                    rgba[0] as f32,
+                    rgba[1] as f32,
+                    rgba[2] as f32,
+                    rgba[3] as f32,
+                ) * (1.0 / 255.0)
+            })
+            .collect();
+        Self {
+            width: frame.width,
+            height: frame.height,
+            pixels,
+        }
+    }
+
+    fn to_frame_buffer(&self) -> FrameBuffer {
+        let data = self
+            .pixels
+            .iter()
+            .flat_map(|p| [p.x, p.y, p.z, p.w].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect();
+        FrameBuffer::new(self.width, self.height, data)
+    }
+
+    // 超出范围的坐标取边缘像素
+    fn pixel(&self, x: i32, y: i32) -> Vec4 {
+        let x = x.clamp(0, self.width as i32 - 1);
+        let y = y.clamp(0, self.height as i32 - 1);
+        self.pixels[(y * self.width as i32 + x) as usize]
+    }
+
+    // 双线性采样，像素(x, y)的中心为(x + 0.5, y + 0.5)
+    fn sample(&self, p: Vec2) -> Vec4 {
+        let x = p.x - 0.5;
+        let y = p.y - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let bottom = self.pixel(x0, y0).lerp(self.pixel(x0 + 1, y0), tx);
+        let top = self.pixel(x0, y0 + 1).lerp(self.pixel(x0 + 1, y0 + 1), tx);
+        bottom.lerp(top, ty)
+    }
+}
@@ -1,12 +1,23 @@
+use gltf::material::AlphaMode;
+
 use crate::{
+    blend::BlendState,
     camera::Camera,
     color::Color,
-    light::PointLight,
+    edge::{add, snap, TriangleEdges, SUBPIXEL_SCALE},
+    framebuffer::FrameBuffer,
+    light::Light,
     material::Material,
-    math::{Mat4, Vec2, Vec3},
-    mesh::{Mesh, Vertex},
+    math::{Mat4, Vec2, Vec3, Vec4},
+    mesh::{Mesh, Topology, Vertex},
+    msaa::SampleCount,
+    oit::{Fragment, FragmentLists},
+    scene::Scene,
     shader::{FragmentShader, FragmentShaderPayload, VertexShader},
+    shadow::{LightShadow, ShadowSettings},
     texture::TextureStorage,
+    tile::Tile,
+    transform::normal_matrix,
 };
 
 //// 视口
@@ -30,15 +41,52 @@ impl Viewport {
     }
 }
 
+// 裁剪矩形（与视口使用相同的屏幕坐标）
+#[derive(Debug, Clone, Copy)]
+pub struct Scissor {
+    // 裁剪矩形左下角的坐标
+    pub x: i32,
+    pub y: i32,
+    // 裁剪矩形的宽高
+    pub width: u32,
+    pub height: u32,
+}
+impl Scissor {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct RendererSettings {
     pub projection: Projection,
+    // 裁剪矩形，只绘制视口与裁剪矩形相交的区域（None表示整个视口）
+    pub scissor: Option<Scissor>,
+    // 清屏颜色（默认为不透明的黑色）
+    pub clear_color: Color,
     // 是否绘制线框
     pub wireframe: bool,
     // 是否根据顶点颜色插值填充
     pub vertex_color_interp: bool,
     // 是否采用片段着色
     pub fragment_shading: bool,
+    // 点图元的像素大小（小于1时按1绘制）
+    pub point_size: f32,
+    // 阴影设置（None表示不渲染阴影）
+    pub shadow: Option<ShadowSettings>,
+    // 半透明（AlphaMode::Blend）材质的混合方程
+    pub blend: BlendState,
+    // 半透明片段的合成方式
+    pub transparency: TransparencyMode,
+    // 多重采样抗锯齿的每像素采样数
+    pub msaa: SampleCount,
+    // 多线程分块光栅化（需要启用parallel特性；绘制线框时按串行光栅化）
+    pub parallel: bool,
 }
 #[derive(Debug, Clone, Copy, Default)]
 pub enum Projection {
@@ -46,6 +94,14 @@ pub enum Projection {
     Perspective,
     Orthographic,
 }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransparencyMode {
+    // 按三角形从后往前排序后混合，相交的半透明三角形可能出错
+    #[default]
+    Sorted,
+    // 顺序无关透明：片段存入逐像素链表，每个像素单独排序后混合
+    LinkedList,
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct Aabb2d {
@@ -58,77 +114,208 @@ impl Aabb2d {
     }
 }
 
+// 齐次裁剪空间中的六个裁剪平面，可见点满足 -w <= x, y, z <= w（即plane·position >= 0）
+const CLIP_PLANES: [Vec4; 6] = [
+    // 左
+    Vec4::new(1.0, 0.0, 0.0, 1.0),
+    // 右
+    Vec4::new(-1.0, 0.0, 0.0, 1.0),
+    // 下
+    Vec4::new(0.0, 1.0, 0.0, 1.0),
+    // 上
+    Vec4::new(0.0, -1.0, 0.0, 1.0),
+    // 近（近平面映射到z = 1）
+    Vec4::new(0.0, 0.0, -1.0, 1.0),
+    // 远（远平面映射到z = -1）
+    Vec4::new(0.0, 0.0, 1.0, 1.0),
+];
+
+// 等待光栅化的三角形（已完成视口变换）
+#[derive(Debug, Clone, Copy)]
+pub struct RasterTriangle {
+    pub triangle: [Vertex; 3],
+    pub world_positions: [Vec3; 3],
+    pub view_space_positions: [Vec3; 3],
+    pub material: Material,
+}
+impl RasterTriangle {
+    // 视图空间中重心的z值，用于从后往前排序
+    pub fn depth(&self) -> f32 {
+        self.view_space_positions.iter().map(|p| p.z).sum::<f32>() / 3.0
+    }
+}
+
+// 裁剪顶点（裁剪时除顶点属性外，世界坐标和视图空间坐标也需要插值）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClipVertex {
+    pub vertex: Vertex,
+    pub world_position: Vec3,
+    pub view_space_position: Vec3,
+}
+impl ClipVertex {
+    pub fn lerp(&self, rhs: &ClipVertex, t: f32) -> ClipVertex {
+        ClipVertex {
+            vertex: self.vertex.lerp(&rhs.vertex, t),
+            world_position: self.world_position.lerp(rhs.world_position, t),
+            view_space_position: self.view_space_position.lerp(rhs.view_space_position, t),
+        }
+    }
+}
+
 pub struct Renderer {
     pub camera: Camera,
     pub viewport: Viewport,
     pub settings: RendererSettings,
     pub vertex_shader: Option<VertexShader>,
     pub fragment_shader: Option<FragmentShader>,
-    // 帧缓冲
+    // 帧缓冲（RGBA8，颜色通道为sRGB编码）
     pub frame_buffer: Vec<u8>,
-    // 深度缓冲
+    // 多重采样的颜色缓冲（RGBA8，每个像素的采样点连续存储），resolve后写入frame_buffer
+    // 不进行多重采样时为空，直接写入frame_buffer
+    pub sample_buffer: Vec<u8>,
+    // 深度缓冲（每个像素的采样点连续存储）
     pub depth_buffer: Vec<f32>,
+    // 阴影贴图（与光源一一对应，不投射阴影的光源为None）
+    pub shadow_maps: Vec<Option<LightShadow>>,
+    // 等待混合的半透明三角形
+    pub transparent_triangles: Vec<RasterTriangle>,
+    // 逐像素链表（TransparencyMode::LinkedList）
+    pub fragment_lists: FragmentLists,
 }
 impl Renderer {
     pub fn new(camera: Camera, viewport: Viewport, settings: RendererSettings) -> Self {
         let pixel_count = (viewport.width * viewport.height) as usize;
+        let sample_count = settings.msaa.count();
+        let buffered_sample_count = if sample_count > 1 {
+            pixel_count * sample_count
+        } else {
+            0
+        };
         Self {
             camera,
             viewport,
             settings,
             vertex_shader: None,
             fragment_shader: None,
-            frame_buffer: vec![0; pixel_count * 3],
-            depth_buffer: vec![std::f32::MIN; pixel_count],
+            frame_buffer: settings.clear_color.to_rgba8().repeat(pixel_count),
+            sample_buffer: settings
+                .clear_color
+                .to_rgba8()
+                .repeat(buffered_sample_count),
+            depth_buffer: vec![std::f32::MIN; pixel_count * sample_count],
+            shadow_maps: Vec::new(),
+            transparent_triangles: Vec::new(),
+            fragment_lists: FragmentLists::new(pixel_count),
+        }
+    }
+
+    // 按节点层级绘制场景，每个节点使用自己的模型变换
+    pub fn draw_scene(
+        &mut self,
+        scene: &Scene,
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        self.render_shadow_maps(scene, lights);
+        for (meshes, model_transformation) in scene.instances() {
+            self.draw_meshes(meshes, model_transformation, lights, texture_storage);
         }
+        self.draw_transparent(lights, texture_storage);
+        self.resolve();
     }
 
+    // 从每个光源的视角渲染阴影贴图（settings.shadow为None时清空阴影贴图）
+    pub fn render_shadow_maps(&mut self, scene: &Scene, lights: &[Light]) {
+        self.shadow_maps.clear();
+        let Some(settings) = self.settings.shadow else {
+            return;
+        };
+        let Some(bounds) = scene.bounding_sphere() else {
+            return;
+        };
+        self.shadow_maps = lights
+            .iter()
+            .map(|light| {
+                let mut shadow_map = LightShadow::new(light, bounds, settings)?;
+                shadow_map.render(scene);
+                Some(shadow_map)
+            })
+            .collect();
+    }
+
+    // 绘制网格，最后绘制其中的半透明三角形
     pub fn draw(
         &mut self,
-        meshes: &Vec<Mesh>,
+        meshes: &[Mesh],
         model_transformation: Mat4,
-        light: PointLight,
+        lights: &[Light],
         texture_storage: &TextureStorage,
     ) {
-        for mesh in meshes.iter() {
-            for i in 0..mesh.vertices.len() / 3 {
-                let mut triangle = [
-                    mesh.vertices[i * 3],
-                    mesh.vertices[1 + i * 3],
-                    mesh.vertices[2 + i * 3],
-                ];
+        self.draw_meshes(meshes, model_transformation, lights, texture_storage);
+        self.draw_transparent(lights, texture_storage);
+        self.resolve();
+    }
 
-                let world_positions: [Vec3; 3] = triangle.map(|v| v.position.to_cartesian_point());
+    // 绘制网格，半透明三角形加入transparent_triangles等待draw_transparent
+    pub fn draw_meshes(
+        &mut self,
+        meshes: &[Mesh],
+        model_transformation: Mat4,
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        for mesh in meshes.iter() {
+            match mesh.topology {
+                Topology::Triangles => {
+                    self.draw_triangles(mesh, model_transformation, lights, texture_storage)
+                }
+                Topology::Lines => self.draw_lines(mesh, model_transformation),
+                Topology::Points => self.draw_points(mesh, model_transformation),
+            }
+        }
+    }
 
-                // 顶点着色
-                self.vertex_shading(&mut triangle);
+    pub fn draw_triangles(
+        &mut self,
+        mesh: &Mesh,
+        model_transformation: Mat4,
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        // 每个顶点只变换一次，三角形通过索引取变换后的顶点
+        let vertices = self.transform_vertices(mesh, model_transformation);
 
-                // 模型变换
-                self.apply_model_transformation(&mut triangle, model_transformation);
+        // 分块光栅化时先收集所有三角形
+        let mut batch = Vec::new();
+        for i in 0..mesh.index_count() / 3 {
+            let triangle = [0, 1, 2].map(|j| vertices[mesh.index(i * 3 + j)]);
+            let view_space_positions = triangle.map(|v| v.view_space_position);
 
-                // 视图变换
-                self.apply_view_transformation(&mut triangle);
+            // 背面剔除（透视投影的视线方向为相机指向三角形的方向，正交投影为-z）
+            let view_direction = match self.settings.projection {
+                Projection::Perspective => view_space_positions[0],
+                Projection::Orthographic => Vec3::NEG_Z,
+            };
+            if Self::back_face_cull(view_space_positions, view_direction) {
+                continue;
+            }
 
-                // 背面剔除
-                if Self::back_face_cull(
-                    triangle.map(|v| v.position.to_cartesian_point()),
-                    Vec3::NEG_Z,
-                ) {
-                    continue;
-                }
+            // 视椎体剔除
+            if Self::frustum_cull(&triangle.map(|v| v.vertex.position)) {
+                continue;
+            }
 
-                // 保存视图空间坐标
-                let view_space_positions: [Vec3; 3] =
-                    triangle.map(|v| v.position.to_cartesian_point());
+            // 齐次裁剪
+            let polygon = Self::homogeneous_clip(triangle.to_vec());
 
-                // 投影变换
-                self.apply_projection_transformation(&mut triangle);
+            // 裁剪后的凸多边形按三角扇重新三角化
+            for j in 1..polygon.len().saturating_sub(1) {
+                let clipped = [polygon[0], polygon[j], polygon[j + 1]];
+                let mut triangle = clipped.map(|v| v.vertex);
 
                 // 透视（齐次）除法
                 Self::homogeneous_division(&mut triangle);
 
-                // TODO 视椎体裁剪
-
                 // 视口变换
                 self.apply_viewport_transformation(&mut triangle);
 
@@ -137,77 +324,484 @@ impl Renderer {
                     self.draw_wireframe(&triangle, Color::WHITE);
                 }
 
-                // 光栅化
-                self.rasterize_trianlge(
-                    world_positions,
-                    view_space_positions,
+                let raster = RasterTriangle {
                     triangle,
-                    &mesh.material,
-                    &light,
-                    texture_storage,
-                );
+                    world_positions: clipped.map(|v| v.world_position),
+                    view_space_positions: clipped.map(|v| v.view_space_position),
+                    material: mesh.material,
+                };
+
+                // 半透明三角形在所有不透明物体之后绘制
+                if mesh.material.alpha_mode == AlphaMode::Blend {
+                    self.transparent_triangles.push(raster);
+                } else if self.tiled() {
+                    batch.push(raster);
+                } else {
+                    // 光栅化
+                    self.rasterize_trianlge(&raster, lights, texture_storage);
+                }
+            }
+        }
+        self.rasterize_triangles(&batch, lights, texture_storage);
+    }
+
+    // 透明通道：半透明三角形从后往前排序后与帧缓冲混合
+    // LinkedList模式下不排序三角形，光栅化后逐像素合成
+    pub fn draw_transparent(&mut self, lights: &[Light], texture_storage: &TextureStorage) {
+        let mut triangles = std::mem::take(&mut self.transparent_triangles);
+        if self.settings.transparency == TransparencyMode::Sorted {
+            // 视图空间中z越小越远
+            triangles.sort_by(|a, b| a.depth().total_cmp(&b.depth()));
+        }
+        self.rasterize_triangles(&triangles, lights, texture_storage);
+        self.resolve_fragment_lists();
+    }
+
+    // 将每个像素链表中的片段从远到近与帧缓冲混合，然后清空链表
+    pub fn resolve_fragment_lists(&mut self) {
+        let mut target = self.take_render_target();
+        self.resolve_tile_fragment_lists(&mut target);
+        self.restore_render_target(target);
+    }
+
+    fn resolve_tile_fragment_lists(&self, tile: &mut Tile) {
+        if tile.fragment_lists.is_empty() {
+            return;
+        }
+        for index in 0..tile.fragment_lists.heads.len() {
+            if tile.fragment_lists.heads[index].is_none() {
+                continue;
+            }
+            for fragment in tile.fragment_lists.sorted_fragments(index) {
+                for sample in 0..tile.sample_count {
+                    if fragment.coverage & (1 << sample) != 0 {
+                        tile.blend_sample(index, sample, fragment.color, &self.settings.blend);
+                    }
+                }
+            }
+        }
+        tile.fragment_lists.clear();
+    }
+
+    // 是否使用多线程分块光栅化
+    fn tiled(&self) -> bool {
+        cfg!(feature = "parallel") && self.settings.parallel && !self.settings.wireframe
+    }
+
+    // 按顺序光栅化一批三角形
+    pub fn rasterize_triangles(
+        &mut self,
+        triangles: &[RasterTriangle],
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        #[cfg(feature = "parallel")]
+        if self.tiled() {
+            self.rasterize_tiled(triangles, lights, texture_storage);
+            return;
+        }
+        for raster in triangles {
+            self.rasterize_trianlge(raster, lights, texture_storage);
+        }
+    }
+
+    // 分块并行光栅化：把可绘制区域划分为块，按包围盒将三角形分配到与之相交的块中，
+    // 每个块在自己的颜色、深度缓冲中按提交顺序光栅化，最后写回帧缓冲
+    // 每个像素上三角形的处理顺序与串行相同，结果与串行光栅化完全一致
+    #[cfg(feature = "parallel")]
+    fn rasterize_tiled(
+        &mut self,
+        triangles: &[RasterTriangle],
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        use rayon::prelude::*;
+
+        if triangles.is_empty() {
+            return;
+        }
+        let (min_x, min_y, max_x, max_y) = self.scissor_rect();
+        let mut tiles = Tile::split(
+            min_x,
+            min_y,
+            (max_x - min_x) as u32,
+            (max_y - min_y) as u32,
+            self.sample_count(),
+        );
+
+        // 分箱
+        let mut bins = vec![Vec::new(); tiles.len()];
+        for (i, raster) in triangles.iter().enumerate() {
+            let Some((x0, y0, x1, y1)) =
+                self.pixel_bounds(&raster.triangle, (min_x, min_y, max_x, max_y))
+            else {
+                continue;
+            };
+            for (tile, bin) in tiles.iter().zip(bins.iter_mut()) {
+                let (tile_x0, tile_y0, tile_x1, tile_y1) = tile.rect();
+                if x0 < tile_x1 && x1 >= tile_x0 && y0 < tile_y1 && y1 >= tile_y0 {
+                    bin.push(i);
+                }
+            }
+        }
+
+        let mut target = self.take_render_target();
+        let renderer = &*self;
+        tiles
+            .par_iter_mut()
+            .zip(bins.par_iter())
+            .filter(|(_, bin)| !bin.is_empty())
+            .for_each(|(tile, bin)| {
+                tile.load(&target);
+                for &i in bin {
+                    renderer.rasterize_tile(tile, &triangles[i], lights, texture_storage);
+                }
+                renderer.resolve_tile_fragment_lists(tile);
+            });
+        for (tile, bin) in tiles.iter().zip(bins.iter()) {
+            if !bin.is_empty() {
+                tile.store(&mut target);
             }
         }
+        self.restore_render_target(target);
+    }
+
+    // 覆盖整个视口的渲染目标，暂时取出帧缓冲、深度缓冲和逐像素链表
+    fn take_render_target(&mut self) -> Tile {
+        let sample_count = self.sample_count();
+        let color_buffer = if sample_count > 1 {
+            std::mem::take(&mut self.sample_buffer)
+        } else {
+            std::mem::take(&mut self.frame_buffer)
+        };
+        Tile {
+            x: self.viewport.x,
+            y: self.viewport.y,
+            width: self.viewport.width,
+            height: self.viewport.height,
+            sample_count,
+            color_buffer,
+            depth_buffer: std::mem::take(&mut self.depth_buffer),
+            fragment_lists: std::mem::take(&mut self.fragment_lists),
+        }
+    }
+
+    fn restore_render_target(&mut self, target: Tile) {
+        if target.sample_count > 1 {
+            self.sample_buffer = target.color_buffer;
+        } else {
+            self.frame_buffer = target.color_buffer;
+        }
+        self.depth_buffer = target.depth_buffer;
+        self.fragment_lists = target.fragment_lists;
+    }
+
+    // 每像素采样数
+    pub fn sample_count(&self) -> usize {
+        self.settings.msaa.count()
+    }
+
+    // 将每个像素所有采样点的颜色取平均写入frame_buffer（不进行多重采样时无需resolve）
+    // 缓冲中的颜色为sRGB编码，在线性空间中取平均
+    pub fn resolve(&mut self) {
+        let sample_count = self.sample_count();
+        if sample_count == 1 {
+            return;
+        }
+        for (pixel, samples) in self
+            .frame_buffer
+            .chunks_exact_mut(4)
+            .zip(self.sample_buffer.chunks_exact(sample_count * 4))
+        {
+            let sum = samples
+                .chunks_exact(4)
+                .map(|sample| Color::from_rgba8([sample[0], sample[1], sample[2], sample[3]]))
+                .fold(Vec4::ZERO, |sum, color| sum + color.to_vec4());
+            let average = Color::from_vec4(sum * (1.0 / sample_count as f32));
+            pixel.copy_from_slice(&average.to_rgba8());
+        }
+    }
+
+    pub fn draw_lines(&mut self, mesh: &Mesh, model_transformation: Mat4) {
+        let vertices = self.transform_vertices(mesh, model_transformation);
+        for i in 0..mesh.index_count() / 2 {
+            let line = [0, 1].map(|j| vertices[mesh.index(i * 2 + j)].vertex);
+
+            // 齐次裁剪
+            let Some((v0, v1)) = Self::homogeneous_clip_line(&line[0], &line[1]) else {
+                continue;
+            };
+            let mut line = [v0, v1];
+            Self::homogeneous_division(&mut line);
+            self.apply_viewport_transformation(&mut line);
+
+            let color = line[0].color.unwrap_or(Color::WHITE);
+            self.draw_line(
+                Vec2::new(line[0].position.x, line[0].position.y),
+                Vec2::new(line[1].position.x, line[1].position.y),
+                color,
+            );
+        }
+    }
+
+    pub fn draw_points(&mut self, mesh: &Mesh, model_transformation: Mat4) {
+        let vertices = self.transform_vertices(mesh, model_transformation);
+        for i in 0..mesh.index_count() {
+            let mut point = [vertices[mesh.index(i)].vertex];
+
+            // 点在视椎体外则剔除
+            if CLIP_PLANES
+                .iter()
+                .any(|plane| plane.dot(point[0].position) < 0.0)
+            {
+                continue;
+            }
+            Self::homogeneous_division(&mut point);
+            self.apply_viewport_transformation(&mut point);
+
+            let color = point[0].color.unwrap_or(Color::WHITE);
+            self.draw_point_sprite(point[0].position.truncate(), color);
+        }
+    }
+
+    // 以点为中心绘制point_size大小的方形点精灵（进行深度测试）
+    pub fn draw_point_sprite(&mut self, center: Vec3, color: Color) {
+        let size = self.settings.point_size.max(1.0);
+        let (min_x, min_y, max_x, max_y) = self.scissor_rect();
+        let x0 = ((center.x - size / 2.0).round() as i32).max(min_x);
+        let y0 = ((center.y - size / 2.0).round() as i32).max(min_y);
+        let x1 = ((center.x - size / 2.0).round() as i32 + size as i32).min(max_x);
+        let y1 = ((center.y - size / 2.0).round() as i32 + size as i32).min(max_y);
+        let mut target = self.take_render_target();
+        let sample_count = target.sample_count;
+        for x in x0..x1 {
+            for y in y0..y1 {
+                let index = target.index(x, y);
+                for sample in 0..sample_count {
+                    let depth = &mut target.depth_buffer[index * sample_count + sample];
+                    if center.z > *depth {
+                        *depth = center.z;
+                        target.write_sample(index, sample, color);
+                    }
+                }
+            }
+        }
+        self.restore_render_target(target);
     }
 
     pub fn rasterize_trianlge(
         &mut self,
-        world_positions: [Vec3; 3],
-        view_space_positions: [Vec3; 3],
-        triangle: [Vertex; 3],
-        material: &Material,
-        light: &PointLight,
+        raster: &RasterTriangle,
+        lights: &[Light],
         texture_storage: &TextureStorage,
     ) {
-        // 包围盒
+        let mut target = self.take_render_target();
+        self.rasterize_tile(&mut target, raster, lights, texture_storage);
+        self.restore_render_target(target);
+    }
+
+    // 三角形覆盖的像素范围(x0, y0, x1, y1)（包含x1、y1），限制在rect (min_x, min_y, max_x, max_y)内
+    // 多重采样时采样点可以偏离像素中心半个像素
+    fn pixel_bounds(
+        &self,
+        triangle: &[Vertex; 3],
+        (min_x, min_y, max_x, max_y): (i32, i32, i32, i32),
+    ) -> Option<(i32, i32, i32, i32)> {
         let aabb2d = bounding_box2d(&triangle.map(|v| Vec2::new(v.position.x, v.position.y)));
+        let pad = if self.sample_count() > 1 { 0.5 } else { 0.0 };
+        let x0 = ((aabb2d.min.x - pad).floor() as i32).max(min_x);
+        let y0 = ((aabb2d.min.y - pad).floor() as i32).max(min_y);
+        let x1 = ((aabb2d.max.x + pad).ceil() as i32).min(max_x - 1);
+        let y1 = ((aabb2d.max.y + pad).ceil() as i32).min(max_y - 1);
+        (x0 <= x1 && y0 <= y1).then_some((x0, y0, x1, y1))
+    }
 
-        // 光栅化
-        for x in aabb2d.min.x as u32..=aabb2d.max.x as u32 {
-            for y in aabb2d.min.y as u32..=aabb2d.max.y as u32 {
-                // 计算屏幕三角形重心坐标
-                let p = Vec2::new(x as f32, y as f32);
-                let barycenter = barycentric_2d_triangle(p, &triangle);
+    // 在tile覆盖的区域内光栅化三角形
+    fn rasterize_tile(
+        &self,
+        tile: &mut Tile,
+        raster: &RasterTriangle,
+        lights: &[Light],
+        texture_storage: &TextureStorage,
+    ) {
+        let RasterTriangle {
+            triangle,
+            world_positions,
+            view_space_positions,
+            ref material,
+        } = *raster;
 
-                // 判断是否在三角形内
-                if Self::inside_triangle(barycenter) {
-                    let z = Self::z_interpolation(&triangle, barycenter);
-                    let index = (y * self.viewport.width + x) as usize;
+        // 包围盒限制在视口、裁剪矩形和tile内
+        let (min_x, min_y, max_x, max_y) = self.scissor_rect();
+        let (tile_x0, tile_y0, tile_x1, tile_y1) = tile.rect();
+        let rect = (
+            min_x.max(tile_x0),
+            min_y.max(tile_y0),
+            max_x.min(tile_x1),
+            max_y.min(tile_y1),
+        );
+        let Some((x0, y0, x1, y1)) = self.pixel_bounds(&triangle, rect) else {
+            return;
+        };
+
+        // 边函数（顶点吸附到子像素网格上），退化的三角形不覆盖任何像素
+        let Some(edges) =
+            TriangleEdges::new(triangle.map(|v| Vec2::new(v.position.x, v.position.y)))
+        else {
+            return;
+        };
+        let step_x = edges.step_x();
+        let step_y = edges.step_y();
+
+        // 采样点相对像素中心的偏移对应的边函数增量
+        let sample_steps: Vec<[i64; 3]> = self
+            .settings
+            .msaa
+            .positions()
+            .iter()
+            .map(|offset| {
+                let offset = snap(*offset);
+                edges
+                    .edges
+                    .map(|edge| edge.a * offset.0 + edge.b * offset.1)
+            })
+            .collect();
+        let sample_count = sample_steps.len();
+
+        // 光栅化：逐行扫描包围盒，边函数值随像素递增
+        let mut row = edges.evaluate((x0 as i64 * SUBPIXEL_SCALE, y0 as i64 * SUBPIXEL_SCALE));
+        for y in y0..=y1 {
+            let mut center = row;
+            row = add(row, step_y);
+            for x in x0..=x1 {
+                // 像素中心的边函数值
+                let values = center;
+                center = add(center, step_x);
+                let index = tile.index(x, y);
 
+                // 逐采样点的覆盖测试和深度测试
+                let mut coverage = 0u32;
+                let mut sample_depths = [0.0; SampleCount::MAX];
+                // 着色点：像素中心在三角形内时取像素中心，否则取第一个被覆盖的采样点
+                let mut shading_point = None;
+                for (sample, step) in sample_steps.iter().enumerate() {
+                    let sample_values = add(values, *step);
+                    // 判断是否在三角形内（左上填充规则）
+                    if !edges.covers(sample_values) {
+                        continue;
+                    }
+                    // 重心坐标
+                    let barycenter = edges.barycentric(sample_values);
+                    let z = Self::z_interpolation(&triangle, barycenter);
+                    shading_point.get_or_insert(sample_values);
                     // 深度测试
-                    if z > self.depth_buffer[index] {
-                        self.depth_buffer[index] = z;
-
-                        // 透视矫正
-                        let barycenter = Self::perspective_correct(&triangle, barycenter);
-
-                        if self.settings.fragment_shading {
-                            // 片段着色
-                            if let Some(fragment_shader) = &self.fragment_shader {
-                                let fragment_shader_payload = FragmentShaderPayload {
-                                    triangle,
-                                    world_positions,
-                                    view_space_positions,
-                                    barycenter,
-                                    light: light.clone(),
-                                    camera_world_position: self.camera.position,
-                                    material: material.clone(),
-                                    ..Default::default()
-                                };
-                                let color =
-                                    fragment_shader(&fragment_shader_payload, texture_storage);
-                                self.draw_pixel(p, color);
+                    if z <= tile.depth_buffer[index * sample_count + sample] {
+                        continue;
+                    }
+                    coverage |= 1 << sample;
+                    sample_depths[sample] = z;
+                }
+                if coverage == 0 {
+                    continue;
+                }
+                let shading_values = if edges.covers(values) {
+                    values
+                } else {
+                    shading_point.unwrap()
+                };
+                let barycenter = edges.barycentric(shading_values);
+                let z = Self::z_interpolation(&triangle, barycenter);
+
+                // 透视矫正
+                let barycenter = Self::perspective_correct(&triangle, barycenter);
+
+                // 每个像素只着色一次
+                let mut color = None;
+                if self.settings.fragment_shading {
+                    // 片段着色
+                    if let Some(fragment_shader) = &self.fragment_shader {
+                        // 相邻像素的重心坐标之差，用于计算纹理坐标的屏幕空间导数
+                        let barycenter_delta = |step: [i64; 3]| {
+                            let neighbor = Self::perspective_correct(
+                                &triangle,
+                                edges.barycentric(add(shading_values, step)),
+                            );
+                            (
+                                neighbor.0 - barycenter.0,
+                                neighbor.1 - barycenter.1,
+                                neighbor.2 - barycenter.2,
+                            )
+                        };
+                        let fragment_shader_payload = FragmentShaderPayload {
+                            triangle,
+                            world_positions,
+                            world_normals: triangle.map(|v| v.normal),
+                            view_space_positions,
+                            barycenter,
+                            barycenter_ddx: barycenter_delta(step_x),
+                            barycenter_ddy: barycenter_delta(step_y),
+                            lights,
+                            camera_world_position: self.camera.position,
+                            material,
+                            shadow_maps: &self.shadow_maps,
+                        };
+                        color = Some(fragment_shader(&fragment_shader_payload, texture_storage));
+                    }
+                } else if self.settings.vertex_color_interp {
+                    // 顶点颜色插值
+                    if triangle[0].color.is_some()
+                        && triangle[1].color.is_some()
+                        && triangle[2].color.is_some()
+                    {
+                        color = Some(
+                            triangle[0].color.unwrap() * barycenter.0
+                                + triangle[1].color.unwrap() * barycenter.1
+                                + triangle[2].color.unwrap() * barycenter.2,
+                        );
+                    }
+                }
+
+                // 透明度低于阈值的片段被丢弃，不写入深度
+                if material.alpha_mode == AlphaMode::Mask
+                    && color.is_some_and(|color| color.a < material.alpha_cutoff)
+                {
+                    continue;
+                }
+
+                let covered = (0..sample_count).filter(|sample| coverage & (1 << sample) != 0);
+                match material.alpha_mode {
+                    // 没有颜色时（如阴影贴图的深度通道）只写入深度
+                    AlphaMode::Opaque | AlphaMode::Mask => {
+                        for sample in covered {
+                            tile.depth_buffer[index * sample_count + sample] =
+                                sample_depths[sample];
+                            if let Some(color) = color {
+                                tile.write_sample(index, sample, color.with_alpha(1.0));
                             }
-                        } else if self.settings.vertex_color_interp {
-                            // 顶点颜色插值
-                            if triangle[0].color.is_some()
-                                && triangle[1].color.is_some()
-                                && triangle[2].color.is_some()
-                            {
-                                let color = triangle[0].color.unwrap() * barycenter.0
-                                    + triangle[1].color.unwrap() * barycenter.1
-                                    + triangle[2].color.unwrap() * barycenter.2;
-                                self.draw_pixel(p, color);
+                        }
+                    }
+                    AlphaMode::Blend => {
+                        // 半透明片段与帧缓冲混合（或存入链表），不写入深度
+                        if let Some(color) = color {
+                            match self.settings.transparency {
+                                TransparencyMode::Sorted => {
+                                    for sample in covered {
+                                        tile.blend_sample(
+                                            index,
+                                            sample,
+                                            color,
+                                            &self.settings.blend,
+                                        );
+                                    }
+                                }
+                                TransparencyMode::LinkedList => tile.fragment_lists.insert(
+                                    index,
+                                    Fragment {
+                                        color,
+                                        depth: z,
+                                        coverage,
+                                    },
+                                ),
                             }
                         }
                     }
@@ -216,6 +810,53 @@ impl Renderer {
         }
     }
 
+    // 对网格的每个顶点进行顶点着色和模型、视图、投影变换（变换到齐次裁剪空间）
+    // 同时保存世界坐标和视图空间坐标
+    pub fn transform_vertices(
+        &mut self,
+        mesh: &Mesh,
+        model_transformation: Mat4,
+    ) -> Vec<ClipVertex> {
+        let mut vertices = mesh.vertices.clone();
+
+        // 顶点着色
+        self.vertex_shading(&mut vertices);
+
+        // 模型变换
+        self.apply_model_transformation(&mut vertices, model_transformation);
+
+        // 保存世界坐标
+        let world_positions: Vec<Vec3> = vertices
+            .iter()
+            .map(|v| v.position.to_cartesian_point())
+            .collect();
+
+        // 视图变换
+        self.apply_view_transformation(&mut vertices);
+
+        // 保存视图空间坐标
+        let view_space_positions: Vec<Vec3> = vertices
+            .iter()
+            .map(|v| v.position.to_cartesian_point())
+            .collect();
+
+        // 投影变换
+        self.apply_projection_transformation(&mut vertices);
+
+        vertices
+            .into_iter()
+            .zip(world_positions)
+            .zip(view_space_positions)
+            .map(
+                |((vertex, world_position), view_space_position)| ClipVertex {
+                    vertex,
+                    world_position,
+                    view_space_position,
+                },
+            )
+            .collect()
+    }
+
     pub fn vertex_shading(&self, vertices: &mut [Vertex]) {
         if let Some(vertex_shader) = &self.vertex_shader {
             for vertex in vertices.iter_mut() {
@@ -224,9 +865,20 @@ impl Renderer {
         }
     }
 
+    // 模型变换后顶点位置和法线都在世界空间中（之后的变换不再修改法线）
     pub fn apply_model_transformation(&self, vertices: &mut [Vertex], model_transformation: Mat4) {
+        let normal_matrix = normal_matrix(model_transformation);
         for vertex in vertices.iter_mut() {
             vertex.position = model_transformation * vertex.position;
+            // 法线用法线矩阵变换后重新归一化
+            vertex.normal = vertex.normal.map(|n| {
+                let n = normal_matrix * n;
+                if n.length() > 0.0 {
+                    n.normalize()
+                } else {
+                    n
+                }
+            });
         }
     }
 
@@ -239,7 +891,10 @@ impl Renderer {
 
     pub fn apply_projection_transformation(&self, vertices: &mut [Vertex]) {
         let projection_transformation = match self.settings.projection {
-            Projection::Perspective => self.camera.frustum.persp_projection_transformation(),
+            // 透视投影矩阵得到的w为视图空间的z（可见点为负），整个矩阵取反使w = -z，
+            // 所有顶点使用同一符号约定，相机后方的点w < 0，由齐次裁剪去除
+            // （齐次坐标整体乘以非零常数表示同一个点，透视除法的结果不变）
+            Projection::Perspective => self.camera.frustum.persp_projection_transformation() * -1.0,
             Projection::Orthographic => self.camera.frustum.ortho_projection_transformation(),
         };
         for vertex in vertices.iter_mut() {
@@ -260,12 +915,60 @@ impl Renderer {
         }
     }
 
-    pub fn frustum_cull(&self, _triangle: [Vec3; 3]) -> bool {
-        todo!()
+    // 三个顶点都在同一裁剪平面外侧时，三角形完全不可见
+    pub fn frustum_cull(triangle: &[Vec4; 3]) -> bool {
+        CLIP_PLANES
+            .iter()
+            .any(|plane| triangle.iter().all(|p| plane.dot(*p) < 0.0))
+    }
+
+    // Sutherland-Hodgman算法，依次用六个裁剪平面裁剪凸多边形（齐次裁剪空间）
+    pub fn homogeneous_clip(polygon: Vec<ClipVertex>) -> Vec<ClipVertex> {
+        let mut polygon = polygon;
+        for plane in CLIP_PLANES.iter() {
+            if polygon.is_empty() {
+                break;
+            }
+            let mut clipped = Vec::with_capacity(polygon.len() + 1);
+            for i in 0..polygon.len() {
+                let current = &polygon[i];
+                let next = &polygon[(i + 1) % polygon.len()];
+                let d_current = plane.dot(current.vertex.position);
+                let d_next = plane.dot(next.vertex.position);
+
+                if d_current >= 0.0 {
+                    clipped.push(*current);
+                }
+                // 边与裁剪平面相交，插值出交点
+                if (d_current >= 0.0) != (d_next >= 0.0) {
+                    let t = d_current / (d_current - d_next);
+                    clipped.push(current.lerp(next, t));
+                }
+            }
+            polygon = clipped;
+        }
+        polygon
     }
 
-    pub fn homogeneous_clip() {
-        todo!()
+    // 用六个裁剪平面裁剪线段（齐次裁剪空间），线段完全不可见时返回None
+    pub fn homogeneous_clip_line(v0: &Vertex, v1: &Vertex) -> Option<(Vertex, Vertex)> {
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        for plane in CLIP_PLANES.iter() {
+            let d0 = plane.dot(v0.position);
+            let d1 = plane.dot(v1.position);
+            if d0 < 0.0 && d1 < 0.0 {
+                return None;
+            }
+            if d0 < 0.0 {
+                t0 = t0.max(d0 / (d0 - d1));
+            } else if d1 < 0.0 {
+                t1 = t1.min(d0 / (d0 - d1));
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+        Some((v0.lerp(v1, t0), v0.lerp(v1, t1)))
     }
 
     pub fn back_face_cull(triangle: [Vec3; 3], view_direction: Vec3) -> bool {
@@ -274,24 +977,36 @@ impl Renderer {
         normal.dot(view_direction) > 0.0
     }
 
+    // 可绘制区域（视口与裁剪矩形的交集），返回(min_x, min_y, max_x, max_y)，max不包含在内
+    pub fn scissor_rect(&self) -> (i32, i32, i32, i32) {
+        let mut min_x = self.viewport.x;
+        let mut min_y = self.viewport.y;
+        let mut max_x = self.viewport.x + self.viewport.width as i32;
+        let mut max_y = self.viewport.y + self.viewport.height as i32;
+        if let Some(scissor) = self.settings.scissor {
+            min_x = min_x.max(scissor.x);
+            min_y = min_y.max(scissor.y);
+            max_x = max_x.min(scissor.x + scissor.width as i32);
+            max_y = max_y.min(scissor.y + scissor.height as i32);
+        }
+        (min_x, min_y, max_x.max(min_x), max_y.max(min_y))
+    }
+
     // 绘制像素点
     pub fn draw_pixel(&mut self, p0: Vec2, color: Color) {
         let x = p0.x as i32;
         let y = p0.y as i32;
-        if x < self.viewport.x
-            || x >= self.viewport.x + self.viewport.width as i32
-            || y < self.viewport.y
-            || y >= self.viewport.y + self.viewport.height as i32
-        {
-            println!("pixel out of viewport");
+        let (min_x, min_y, max_x, max_y) = self.scissor_rect();
+        if x < min_x || x >= max_x || y < min_y || y >= max_y {
             return;
         }
-        // 以viewport左下角为原点
-        let (x, y) = (x - self.viewport.x, y - self.viewport.y);
-        let index = (y * self.viewport.width as i32 + x) as usize;
-        self.frame_buffer[index * 3] = (color.r * 255.) as u8;
-        self.frame_buffer[index * 3 + 1] = (color.g * 255.) as u8;
-        self.frame_buffer[index * 3 + 2] = (color.b * 255.) as u8;
+        // 写入像素的所有采样点
+        let mut target = self.take_render_target();
+        let index = target.index(x, y);
+        for sample in 0..target.sample_count {
+            target.write_sample(index, sample, color);
+        }
+        self.restore_render_target(target);
     }
 
     pub fn draw_wireframe(&mut self, vertices: &[Vertex], color: Color) {
@@ -305,11 +1020,15 @@ impl Renderer {
     // Bresenham画线算法
     pub fn draw_line(&mut self, p0: Vec2, p1: Vec2, color: Color) {
         // 线段裁剪
+        let (min_x, min_y, max_x, max_y) = self.scissor_rect();
+        if min_x == max_x || min_y == max_y {
+            return;
+        }
         let clip_result = line_clip(
             p0,
             p1,
-            Vec2::ZERO,
-            Vec2::new(self.viewport.width as f32, self.viewport.height as f32),
+            Vec2::new(min_x as f32, min_y as f32),
+            Vec2::new((max_x - 1) as f32, (max_y - 1) as f32),
         );
         if clip_result.is_none() {
             return;
@@ -439,18 +1158,11 @@ impl Renderer {
         })
     }
 
-    pub fn inside_triangle((alpha, beta, gamma): (f32, f32, f32)) -> bool {
-        alpha > 0.0 && beta > 0.0 && gamma > 0.0
-    }
-
+    // 透视除法后的深度在屏幕空间中是线性的，直接用屏幕空间重心坐标插值（不需要透视矫正）
     pub fn z_interpolation(triangle: &[Vertex; 3], (alpha, beta, gamma): (f32, f32, f32)) -> f32 {
-        let v0 = triangle[0].position;
-        let v1 = triangle[1].position;
-        let v2 = triangle[2].position;
-        let w_reciprocal = 1.0 / (alpha / v0.w + beta / v1.w + gamma / v2.w);
-        let mut z_interpolated = alpha * v0.z / v0.w + beta * v1.z / v1.w + gamma * v2.z / v2.w;
-        z_interpolated *= w_reciprocal;
-        z_interpolated
+        alpha * triangle[0].position.z
+            + beta * triangle[1].position.z
+            + gamma * triangle[2].position.z
     }
 
     // TODO 理解透视矫正
@@ -465,29 +1177,41 @@ impl Renderer {
         (w0 * normalizer, w1 * normalizer, w2 * normalizer)
     }
 
+    // 拷贝当前帧缓冲（用于离屏渲染导出图片）
+    pub fn color_frame_buffer(&self) -> FrameBuffer {
+        FrameBuffer::new(
+            self.viewport.width,
+            self.viewport.height,
+            self.frame_buffer.clone(),
+        )
+    }
+
+    // 深度缓冲的灰度可视化
+    // 多重采样时取每个像素最近的采样点
+    pub fn depth_frame_buffer(&self) -> FrameBuffer {
+        let depth_buffer: Vec<f32> = self
+            .depth_buffer
+            .chunks_exact(self.sample_count())
+            .map(|samples| samples.iter().copied().fold(f32::MIN, f32::max))
+            .collect();
+        FrameBuffer::from_depth_buffer(&depth_buffer, self.viewport.width, self.viewport.height)
+    }
+
     pub fn clear(&mut self) {
-        self.frame_buffer.fill(0);
+        let clear_color = self.settings.clear_color.to_rgba8();
+        for pixel in self
+            .frame_buffer
+            .chunks_exact_mut(4)
+            .chain(self.sample_buffer.chunks_exact_mut(4))
+        {
+            pixel.copy_from_slice(&clear_color);
+        }
         self.depth_buffer.fill(f32::MIN);
+        self.transparent_triangles.clear();
+        self.fragment_lists.clear();
     }
 }
 
-// 2D重心坐标
-pub fn barycentric_2d_triangle(p: Vec2, triangle: &[Vertex; 3]) -> (f32, f32, f32) {
-    barycentric_2d(
-        p,
-        Vec2::new(triangle[0].position.x, triangle[0].position.y),
-        Vec2::new(triangle[1].position.x, triangle[1].position.y),
-        Vec2::new(triangle[2].position.x, triangle[2].position.y),
-    )
-}
-pub fn barycentric_2d(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> (f32, f32, f32) {
-    let area_twice = (b - a).cross(c - a);
-    let alpha = (b - p).cross(c - p) / area_twice;
-    let beta = (c - p).cross(a - p) / area_twice;
-    let gamma = (a - p).cross(b - p) / area_twice;
-    (alpha, beta, gamma)
-}
-
 // Cohen-Sutherland线段裁剪算法
 const INSIDE: u8 = 0; // 0000
 const LEFT: u8 = 1; // 0001
@@ -542,7 +1266,7 @@ pub fn line_clip(
             p.x = p0.x + (p1.x - p0.x) * (rect_max.y - p0.y) / (p1.y - p0.y);
             p.y = rect_max.y;
         } else if out_code & BOTTOM != 0 {
-            p.x = p0.x + (p0.x - p0.x) * (rect_min.y - p0.y) / (p1.y - p0.y);
+            p.x = p0.x + (p1.x - p0.x) * (rect_min.y - p0.y) / (p1.y - p0.y);
             p.y = rect_min.y;
         } else if out_code & RIGHT != 0 {
             p.x = rect_max.x;
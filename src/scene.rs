@@ -0,0 +1,95 @@
+use crate::{
+    math::{Mat4, Vec3},
+    mesh::Mesh,
+    transform::Transform,
+};
+
+// 场景节点
+#[derive(Debug, Clone, Default)]
+pub struct Node {
+    pub name: Option<String>,
+    // 相对于父节点的局部变换
+    pub transform: Transform,
+    // 子节点在Scene::nodes中的索引
+    pub children: Vec<usize>,
+    // 节点引用的网格在Scene::meshes中的索引（多个节点可以引用同一网格，即实例化）
+    pub mesh: Option<usize>,
+}
+
+// 场景（节点层级）
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    // 网格，每个网格由若干图元组成
+    pub meshes: Vec<Vec<Mesh>>,
+    pub nodes: Vec<Node>,
+    // 根节点在Scene::nodes中的索引
+    pub roots: Vec<usize>,
+}
+impl Scene {
+    // 由一组图元构建只有一个节点的场景
+    pub fn from_meshes(meshes: Vec<Mesh>) -> Self {
+        Self {
+            meshes: vec![meshes],
+            nodes: vec![Node {
+                mesh: Some(0),
+                ..Default::default()
+            }],
+            roots: vec![0],
+        }
+    }
+
+    // 从根节点开始计算每个节点的世界变换矩阵（不可达的节点为None）
+    pub fn world_transformations(&self) -> Vec<Option<Mat4>> {
+        let mut world_transformations = vec![None; self.nodes.len()];
+        let mut stack: Vec<(usize, Mat4)> = self
+            .roots
+            .iter()
+            .map(|&root| (root, Mat4::IDENTITY))
+            .collect();
+        while let Some((index, parent_transformation)) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            // 忽略环（glTF规定节点层级不能有环）
+            if world_transformations[index].is_some() {
+                continue;
+            }
+            let world_transformation = parent_transformation * node.transform.to_mat4();
+            world_transformations[index] = Some(world_transformation);
+            for &child in node.children.iter() {
+                stack.push((child, world_transformation));
+            }
+        }
+        world_transformations
+    }
+
+    // 需要绘制的网格实例及其模型变换矩阵
+    pub fn instances(&self) -> Vec<(&[Mesh], Mat4)> {
+        self.world_transformations()
+            .into_iter()
+            .zip(self.nodes.iter())
+            .filter_map(|(world_transformation, node)| {
+                let meshes = self.meshes.get(node.mesh?)?;
+                Some((meshes.as_slice(), world_transformation?))
+            })
+            .collect()
+    }
+
+    // 场景的包围球(球心, 半径)（世界空间，由包围盒计算），没有顶点时返回None
+    pub fn bounding_sphere(&self) -> Option<(Vec3, f32)> {
+        let mut min = Vec3::splat(f32::MAX);
+        let mut max = Vec3::splat(f32::MIN);
+        for (meshes, model_transformation) in self.instances() {
+            for vertex in meshes.iter().flat_map(|mesh| mesh.vertices.iter()) {
+                let p = (model_transformation * vertex.position).to_cartesian_point();
+                min = Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+                max = Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            }
+        }
+        if min.x > max.x {
+            return None;
+        }
+        let center = (min + max) * 0.5;
+        Some((center, (max - center).length()))
+    }
+}
@@ -2,29 +2,40 @@ use std::collections::HashMap;
 
 use crate::{
     color::Color,
-    light::PointLight,
+    light::Light,
     material::Material,
     math::{Mat4, Vec2, Vec3, Vec4},
     mesh::Vertex,
-    texture::TextureStorage,
+    shadow::LightShadow,
+    texture::{TexCoord, TextureStorage},
 };
 
-const AMBIENT_LIGHT_INTENSITY: f32 = 0.2;
+// 非金属的基础反射率
+const DIELECTRIC_F0: f32 = 0.04;
+const MIN_ROUGHNESS: f32 = 0.04;
 
-// TODO 使用引用+生命周期
-#[derive(Debug, Clone, Default)]
-pub struct FragmentShaderPayload {
+#[derive(Debug, Clone)]
+pub struct FragmentShaderPayload<'a> {
     pub triangle: [Vertex; 3],
+    // 三个顶点在世界空间中的位置和法线（经过模型变换和法线矩阵变换）
     pub world_positions: [Vec3; 3],
+    pub world_normals: [Option<Vec3>; 3],
     pub view_space_positions: [Vec3; 3],
     pub barycenter: (f32, f32, f32),
-    pub light: PointLight,
+    // 重心坐标在屏幕空间x、y方向上（到右侧、上方相邻像素）的变化量
+    pub barycenter_ddx: (f32, f32, f32),
+    pub barycenter_ddy: (f32, f32, f32),
+    pub lights: &'a [Light],
     pub camera_world_position: Vec3,
-    pub material: Material,
+    pub material: &'a Material,
+    // 阴影贴图（与lights一一对应）
+    pub shadow_maps: &'a [Option<LightShadow>],
 }
 
-pub type VertexShader = Box<dyn Fn(&mut Vertex)>;
-pub type FragmentShader = Box<dyn Fn(&FragmentShaderPayload, &TextureStorage) -> Color>;
+// 着色器需要Send + Sync，以便多线程光栅化时在多个线程中同时调用
+pub type VertexShader = Box<dyn Fn(&mut Vertex) + Send + Sync>;
+pub type FragmentShader =
+    Box<dyn Fn(&FragmentShaderPayload, &TextureStorage) -> Color + Send + Sync>;
 
 #[derive(Debug, Default)]
 pub struct Uniforms {
@@ -37,76 +48,233 @@ pub struct Uniforms {
     pub texture: HashMap<u32, u32>,
 }
 
+// 着色点的世界坐标
+pub fn world_position(payload: &FragmentShaderPayload) -> Vec3 {
+    let (alpha, beta, gamma) = payload.barycenter;
+    let p = payload.world_positions;
+    p[0] * alpha + p[1] * beta + p[2] * gamma
+}
+
+// 着色点的世界空间法线（插值后归一化），顶点没有法线时使用三角形的面法线
+pub fn world_normal(payload: &FragmentShaderPayload) -> Vec3 {
+    let (alpha, beta, gamma) = payload.barycenter;
+    match payload.world_normals {
+        [Some(n0), Some(n1), Some(n2)] => (n0 * alpha + n1 * beta + n2 * gamma).normalize(),
+        _ => {
+            let p = payload.world_positions;
+            (p[1] - p[0]).cross(p[2] - p[0]).normalize()
+        }
+    }
+}
+
+// 插值纹理坐标及其屏幕空间导数（三个顶点都有纹理坐标时）
+pub fn interpolate_texcoord(payload: &FragmentShaderPayload) -> Option<TexCoord> {
+    let triangle = payload.triangle;
+    let texcoords = [
+        triangle[0].texcoord?,
+        triangle[1].texcoord?,
+        triangle[2].texcoord?,
+    ];
+    let interpolate = |(alpha, beta, gamma): (f32, f32, f32)| {
+        texcoords[0] * alpha + texcoords[1] * beta + texcoords[2] * gamma
+    };
+    Some(TexCoord {
+        uv: interpolate(payload.barycenter),
+        ddx: interpolate(payload.barycenter_ddx),
+        ddy: interpolate(payload.barycenter_ddy),
+    })
+}
+
+// 基础颜色（颜色系数与纹理颜色相乘），w为不透明度
+pub fn base_color(
+    material: &Material,
+    texture_storage: &TextureStorage,
+    texcoord: Option<TexCoord>,
+) -> Vec4 {
+    let base_color_factor = Color::from_vec4(material.base_color_factor);
+    match texture_storage.sample(material.base_color_texture, texcoord) {
+        Some(texcolor) => (texcolor * base_color_factor).to_vec4(),
+        None => base_color_factor.to_vec4(),
+    }
+}
+
+// 环境光遮蔽（1为无遮蔽）
+pub fn occlusion(
+    material: &Material,
+    texture_storage: &TextureStorage,
+    texcoord: Option<TexCoord>,
+) -> f32 {
+    texture_storage
+        .sample(material.occlusion_texture, texcoord)
+        .map_or(1.0, |texcolor| {
+            1.0 + material.occlusion_strength * (texcolor.r - 1.0)
+        })
+}
+
+// 自发光
+pub fn emissive(
+    material: &Material,
+    texture_storage: &TextureStorage,
+    texcoord: Option<TexCoord>,
+) -> Vec3 {
+    match texture_storage.sample(material.emissive_texture, texcoord) {
+        Some(texcolor) => texcolor.to_vec3() * material.emissive_factor,
+        None => material.emissive_factor,
+    }
+}
+
+// 第light_index个光源在着色点处的可见度（没有阴影贴图时为1）
+pub fn shadow_visibility(
+    payload: &FragmentShaderPayload,
+    light_index: usize,
+    pos: Vec3,
+    n_dot_l: f32,
+) -> f32 {
+    match payload.shadow_maps.get(light_index) {
+        Some(Some(shadow_map)) => shadow_map.visibility(pos, n_dot_l),
+        _ => 1.0,
+    }
+}
+
 pub fn phong_shader() -> FragmentShader {
     Box::new(|payload, texture_storage| {
-        let world_positions = payload.world_positions;
         let camera_world_position = payload.camera_world_position;
-        let triangle = payload.triangle;
-        let (alpha, beta, gamma) = payload.barycenter;
-        let light = payload.light;
         let material = payload.material;
 
         // 着色点
-        let pos =
-            world_positions[0] * alpha + world_positions[1] * beta + world_positions[2] * gamma;
-        let texcoord = if triangle[0].texcoord.is_some()
-            && triangle[1].texcoord.is_some()
-            && triangle[2].texcoord.is_some()
-        {
-            Some(
-                triangle[0].texcoord.unwrap() * alpha
-                    + triangle[1].texcoord.unwrap() * beta
-                    + triangle[2].texcoord.unwrap() * gamma,
-            )
-        } else {
-            None
-        };
-        let texcolor = if texcoord.is_some() {
-            texture_storage
-                .texture_id_map
-                .get(&0)
-                .map(|texture| texture.sample(texcoord.unwrap()))
-        } else {
-            None
-        };
-
-        // TODO 处理unwrap / 使用宏简化
+        let pos = world_position(payload);
+        let texcoord = interpolate_texcoord(payload);
+        let base_color = base_color(material, texture_storage, texcoord);
+        let opacity = base_color.w;
+        let base_color = base_color.truncate();
+        let occlusion = occlusion(material, texture_storage, texcoord);
+        let emissive = emissive(material, texture_storage, texcoord);
+
         // 法线
-        let n = (triangle[0].normal.unwrap() * alpha
-            + triangle[1].normal.unwrap() * beta
-            + triangle[2].normal.unwrap() * gamma)
-            .normalize();
-        // 入射光线向量
-        let l = (light.position - pos).normalize();
+        let n = world_normal(payload);
         // 视线向量
         let v = (camera_world_position - pos).normalize();
-        // 半程向量
-        let h = (l + v).normalize();
-        // 入射光线距离
-        let r = (light.position - pos).length();
 
         // 环境光
-        let ambient = material.ambient * AMBIENT_LIGHT_INTENSITY;
-        // 漫反射
-        let diffuse = material.diffuse * (light.intensity / (r * r)) * n.dot(l).max(0.0);
-        // 镜面反射
-        let specular = material.specular
-            * (light.intensity / (r * r))
-            * (n.dot(h).max(0.0).powf(material.shininess));
-
-        let light = ambient + diffuse + specular;
-        let (mut r, mut g, mut b) = if let Some(texcolor) = texcolor {
-            (
-                light.x * texcolor.r,
-                light.y * texcolor.g,
-                light.z * texcolor.b,
-            )
-        } else {
-            (light.x, light.y, light.z)
-        };
-        r = r.clamp(0.0, 1.0);
-        g = g.clamp(0.0, 1.0);
-        b = b.clamp(0.0, 1.0);
-        Color::new(r, g, b)
+        let ambient_intensity: f32 = payload.lights.iter().map(|l| l.ambient_intensity()).sum();
+        let mut light = material.ambient * ambient_intensity * occlusion;
+        for (i, source) in payload.lights.iter().enumerate() {
+            let Some((l, intensity)) = source.incident(pos) else {
+                continue;
+            };
+            let n_dot_l = n.dot(l).max(0.0);
+            let intensity = intensity * shadow_visibility(payload, i, pos, n_dot_l);
+            // 半程向量
+            let h = (l + v).normalize();
+            // 漫反射
+            let diffuse = material.diffuse * intensity * n_dot_l;
+            // 镜面反射
+            let specular =
+                material.specular * intensity * (n.dot(h).max(0.0).powf(material.shininess));
+            light = light + diffuse + specular;
+        }
+
+        let color = light * base_color + emissive;
+        Color::rgba(
+            color.x.clamp(0.0, 1.0),
+            color.y.clamp(0.0, 1.0),
+            color.z.clamp(0.0, 1.0),
+            opacity,
+        )
     })
 }
+
+// 基于物理的金属度/粗糙度着色（Cook-Torrance BRDF）
+pub fn pbr_shader() -> FragmentShader {
+    Box::new(|payload, texture_storage| {
+        let material = payload.material;
+
+        // 着色点
+        let pos = world_position(payload);
+        let texcoord = interpolate_texcoord(payload);
+        let base_color = base_color(material, texture_storage, texcoord);
+        let opacity = base_color.w;
+        let base_color = base_color.truncate();
+        let occlusion = occlusion(material, texture_storage, texcoord);
+        let emissive = emissive(material, texture_storage, texcoord);
+
+        // 金属度（B通道）和粗糙度（G通道）
+        let (metallic, roughness) =
+            match texture_storage.sample(material.metallic_roughness_texture, texcoord) {
+                Some(texcolor) => (
+                    material.metallic_factor * texcolor.b,
+                    material.roughness_factor * texcolor.g,
+                ),
+                None => (material.metallic_factor, material.roughness_factor),
+            };
+        let metallic = metallic.clamp(0.0, 1.0);
+        // 避免粗糙度为0时高光无限大
+        let roughness = roughness.clamp(MIN_ROUGHNESS, 1.0);
+
+        // 法线
+        let n = world_normal(payload);
+        // 视线向量
+        let v = (payload.camera_world_position - pos).normalize();
+        let n_dot_v = n.dot(v).max(0.0);
+        // 垂直入射时的菲涅尔反射率，非金属取0.04
+        let f0 = Vec3::splat(DIELECTRIC_F0).lerp(base_color, metallic);
+
+        let mut direct = Vec3::ZERO;
+        for (i, light) in payload.lights.iter().enumerate() {
+            let Some((l, radiance)) = light.incident(pos) else {
+                continue;
+            };
+            let n_dot_l = n.dot(l).max(0.0);
+            let radiance = radiance * shadow_visibility(payload, i, pos, n_dot_l);
+            // 半程向量
+            let h = (l + v).normalize();
+            let n_dot_h = n.dot(h).max(0.0);
+            let h_dot_v = h.dot(v).max(0.0);
+
+            let d = distribution_ggx(n_dot_h, roughness);
+            let g = geometry_smith(n_dot_v, n_dot_l, roughness);
+            let f = fresnel_schlick(h_dot_v, f0);
+
+            let specular = f * (d * g / (4.0 * n_dot_v * n_dot_l + 1e-4));
+            // 金属没有漫反射
+            let k_d = (Vec3::ONE - f) * (1.0 - metallic);
+            let diffuse = k_d * base_color * std::f32::consts::FRAC_1_PI;
+
+            direct = direct + (diffuse + specular) * (radiance * n_dot_l);
+        }
+
+        let ambient_intensity: f32 = payload.lights.iter().map(|l| l.ambient_intensity()).sum();
+        let ambient = base_color * (ambient_intensity * occlusion);
+        let color = ambient + direct + emissive;
+        Color::rgba(
+            color.x.clamp(0.0, 1.0),
+            color.y.clamp(0.0, 1.0),
+            color.z.clamp(0.0, 1.0),
+            opacity,
+        )
+    })
+}
+
+// GGX/Trowbridge-Reitz法线分布函数
+pub fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    a2 / (std::f32::consts::PI * denom * denom)
+}
+
+// Schlick-GGX几何遮蔽函数（直接光照k = (roughness + 1)^2 / 8）
+pub fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    n_dot_x / (n_dot_x * (1.0 - k) + k)
+}
+
+// Smith几何函数，同时考虑视线方向的遮蔽和光线方向的阴影
+pub fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness)
+}
+
+// Schlick菲涅尔近似
+pub fn fresnel_schlick(cos_theta: f32, f0: Vec3) -> Vec3 {
+    f0 + (Vec3::ONE - f0) * (1.0 - cos_theta).clamp(0.0, 1.0).powi(5)
+}
@@ -0,0 +1,287 @@
+use crate::{
+    camera::Camera,
+    light::Light,
+    math::{Mat4, Vec3},
+    renderer::{Projection, Renderer, RendererSettings, Viewport},
+    scene::Scene,
+    texture::TextureStorage,
+};
+
+// 阴影设置
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowSettings {
+    // 阴影贴图的分辨率（宽高相同）
+    pub resolution: u32,
+    // 常量深度偏移（世界空间距离），避免自阴影（shadow acne）
+    pub bias: f32,
+    // 斜率深度偏移，表面与光线越倾斜偏移越大
+    pub slope_bias: f32,
+    // PCF滤波半径（纹素），0表示不滤波
+    pub pcf_radius: u32,
+    // 聚光灯和点光源阴影相机的近平面
+    pub near: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 512,
+            bias: 0.01,
+            slope_bias: 0.02,
+            pcf_radius: 1,
+            near: 0.05,
+        }
+    }
+}
+
+// 阴影贴图，从光源视角渲染的深度图
+#[derive(Debug, Clone)]
+pub struct ShadowMap {
+    pub camera: Camera,
+    pub projection: Projection,
+    pub settings: ShadowSettings,
+    // 深度缓冲（与Renderer::depth_buffer相同，值越大越近）
+    pub depth_buffer: Vec<f32>,
+}
+impl ShadowMap {
+    pub fn new(camera: Camera, projection: Projection, settings: ShadowSettings) -> Self {
+        let pixel_count = (settings.resolution * settings.resolution) as usize;
+        Self {
+            camera,
+            projection,
+            settings,
+            depth_buffer: vec![f32::MIN; pixel_count],
+        }
+    }
+
+    // 只写深度的渲染（关闭片段着色和顶点颜色插值）
+    pub fn render(&mut self, scene: &Scene) {
+        let viewport = Viewport::new(0, 0, self.settings.resolution, self.settings.resolution);
+        let settings = RendererSettings {
+            projection: self.projection,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::new(self.camera, viewport, settings);
+        renderer.draw_scene(scene, &[], &TextureStorage::default());
+        self.depth_buffer = renderer.depth_buffer;
+    }
+
+    // 光源的投影变换矩阵
+    pub fn projection_transformation(&self) -> Mat4 {
+        match self.projection {
+            Projection::Perspective => self.camera.frustum.persp_projection_transformation(),
+            Projection::Orthographic => self.camera.frustum.ortho_projection_transformation(),
+        }
+    }
+
+    // NDC深度转换为到光源相机的线性距离（视图空间-z）
+    pub fn linear_depth(&self, ndc_z: f32) -> f32 {
+        let n = -self.camera.frustum.near;
+        let f = -self.camera.frustum.far;
+        let z = match self.projection {
+            // z_ndc = 2 / (n - f) * ((n + f) / 2 - n * f / z)
+            Projection::Perspective => n * f / ((n + f) / 2.0 - ndc_z * (n - f) / 2.0),
+            // z_ndc = 2 / (n - f) * (z - (n + f) / 2)
+            Projection::Orthographic => ndc_z * (n - f) / 2.0 + (n + f) / 2.0,
+        };
+        -z
+    }
+
+    // 着色点的可见度（0为完全在阴影中，1为完全被照亮），n_dot_l用于计算斜率偏移
+    pub fn visibility(&self, world_position: Vec3, n_dot_l: f32) -> f32 {
+        let view_position = self.camera.view_transformation() * world_position.extend(1.0);
+        let depth = -view_position.z;
+        let clip_position = self.projection_transformation() * view_position;
+        // 在光源相机后方
+        if clip_position.w.abs() < 1e-6 || depth <= 0.0 {
+            return 1.0;
+        }
+        let ndc = clip_position.to_cartesian_point();
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 || ndc.z.abs() > 1.0 {
+            return 1.0;
+        }
+
+        // 与视口变换相同的映射
+        let size = self.settings.resolution as i32;
+        let x = ((ndc.x + 1.0) * (size - 1) as f32 / 2.0).round() as i32;
+        let y = ((ndc.y + 1.0) * (size - 1) as f32 / 2.0).round() as i32;
+
+        // tan(θ)，θ为法线与光线的夹角
+        let n_dot_l = n_dot_l.clamp(1e-2, 1.0);
+        let tan_theta = (1.0 - n_dot_l * n_dot_l).sqrt() / n_dot_l;
+        let bias = self.settings.bias + self.settings.slope_bias * tan_theta.min(10.0);
+
+        // PCF：对周围纹素的深度比较结果取平均，贴图外的采样点取边缘的纹素
+        let radius = self.settings.pcf_radius as i32;
+        let mut lit = 0;
+        let mut total = 0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let sx = (x + dx).clamp(0, size - 1);
+                let sy = (y + dy).clamp(0, size - 1);
+                total += 1;
+                let stored = self.depth_buffer[(sy * size + sx) as usize];
+                // 该纹素没有被任何几何体覆盖
+                if stored == f32::MIN || depth - bias <= self.linear_depth(stored) {
+                    lit += 1;
+                }
+            }
+        }
+        lit as f32 / total as f32
+    }
+}
+
+// 立方体阴影贴图（点光源），六个面分别朝向±x、±y、±z
+#[derive(Debug, Clone)]
+pub struct CubeShadowMap {
+    pub position: Vec3,
+    pub faces: Vec<ShadowMap>,
+}
+impl CubeShadowMap {
+    const FACE_DIRECTIONS: [Vec3; 6] = [
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ];
+
+    pub fn new(position: Vec3, bounds: (Vec3, f32), settings: ShadowSettings) -> Self {
+        let (center, radius) = bounds;
+        let far = ((center - position).length() + radius).max(settings.near * 2.0);
+        let faces = Self::FACE_DIRECTIONS
+            .iter()
+            .map(|&direction| {
+                // 90°视野、宽高比为1的相机，六个面恰好覆盖所有方向
+                let mut camera = Camera::new(
+                    settings.near,
+                    far,
+                    1.0,
+                    std::f32::consts::FRAC_PI_2,
+                    position,
+                );
+                camera.look_to(direction, up_vector(direction));
+                ShadowMap::new(camera, Projection::Perspective, settings)
+            })
+            .collect();
+        Self { position, faces }
+    }
+
+    pub fn render(&mut self, scene: &Scene) {
+        for face in self.faces.iter_mut() {
+            face.render(scene);
+        }
+    }
+
+    // 按光源指向着色点的方向的主轴选择立方体的面
+    pub fn face_index(direction: Vec3) -> usize {
+        let (x, y, z) = (direction.x.abs(), direction.y.abs(), direction.z.abs());
+        if x >= y && x >= z {
+            if direction.x >= 0.0 {
+                0
+            } else {
+                1
+            }
+        } else if y >= z {
+            if direction.y >= 0.0 {
+                2
+            } else {
+                3
+            }
+        } else if direction.z >= 0.0 {
+            4
+        } else {
+            5
+        }
+    }
+
+    pub fn visibility(&self, world_position: Vec3, n_dot_l: f32) -> f32 {
+        let face = &self.faces[Self::face_index(world_position - self.position)];
+        face.visibility(world_position, n_dot_l)
+    }
+}
+
+// 光源的阴影
+#[derive(Debug, Clone)]
+pub enum LightShadow {
+    // 平行光（正交投影）和聚光灯（透视投影）
+    Map(ShadowMap),
+    // 点光源
+    Cube(CubeShadowMap),
+}
+impl LightShadow {
+    // 为光源创建阴影贴图，bounds为场景的包围球(球心, 半径)，环境光返回None
+    pub fn new(light: &Light, bounds: (Vec3, f32), settings: ShadowSettings) -> Option<Self> {
+        let (center, radius) = bounds;
+        let radius = radius.max(1e-3);
+        match light {
+            Light::Directional(light) => {
+                // 相机放在包围球外2r处，近平面r、远平面3r
+                // fov为90°时近平面半宽为r，正交投影恰好覆盖整个包围球
+                let direction = light.direction.normalize();
+                let mut camera = Camera::new(
+                    radius,
+                    3.0 * radius,
+                    1.0,
+                    std::f32::consts::FRAC_PI_2,
+                    center - direction * (2.0 * radius),
+                );
+                camera.look_to(direction, up_vector(direction));
+                Some(LightShadow::Map(ShadowMap::new(
+                    camera,
+                    Projection::Orthographic,
+                    settings,
+                )))
+            }
+            Light::Spot(light) => {
+                let direction = light.direction.normalize();
+                let far = (center - light.position).length() + radius;
+                let fov = (2.0 * light.outer_cone_angle).clamp(1e-2, 170.0f32.to_radians());
+                let mut camera = Camera::new(
+                    settings.near,
+                    far.max(settings.near * 2.0),
+                    1.0,
+                    fov,
+                    light.position,
+                );
+                camera.look_to(direction, up_vector(direction));
+                Some(LightShadow::Map(ShadowMap::new(
+                    camera,
+                    Projection::Perspective,
+                    settings,
+                )))
+            }
+            Light::Point(light) => Some(LightShadow::Cube(CubeShadowMap::new(
+                light.position,
+                (center, radius),
+                settings,
+            ))),
+            Light::Ambient(_) => None,
+        }
+    }
+
+    pub fn render(&mut self, scene: &Scene) {
+        match self {
+            LightShadow::Map(shadow_map) => shadow_map.render(scene),
+            LightShadow::Cube(shadow_map) => shadow_map.render(scene),
+        }
+    }
+
+    // 着色点的可见度（0为完全在阴影中，1为完全被照亮）
+    pub fn visibility(&self, world_position: Vec3, n_dot_l: f32) -> f32 {
+        match self {
+            LightShadow::Map(shadow_map) => shadow_map.visibility(world_position, n_dot_l),
+            LightShadow::Cube(shadow_map) => shadow_map.visibility(world_position, n_dot_l),
+        }
+    }
+}
+
+// 与光线方向不平行的上方向
+fn up_vector(direction: Vec3) -> Vec3 {
+    if direction.dot(Vec3::Y).abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    }
+}
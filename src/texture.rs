@@ -1,11 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use gltf::{
     image::Format,
     texture::{MagFilter, MinFilter, WrappingMode},
 };
 
-use crate::{color::Color, math::Vec2};
+use crate::{
+    color::{srgb_to_linear, Color},
+    math::{Vec2, Vec4},
+};
 
 #[derive(Clone, Debug)]
 pub struct Sampler {
@@ -15,47 +18,306 @@ pub struct Sampler {
     pub wrap_t: WrappingMode,
 }
 
+// 纹理坐标及其屏幕空间导数（相邻像素间纹理坐标的变化，用于计算mipmap层级）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TexCoord {
+    pub uv: Vec2,
+    pub ddx: Vec2,
+    pub ddy: Vec2,
+}
+impl TexCoord {
+    // 没有导数信息时总是采样第0层
+    pub fn new(uv: Vec2) -> Self {
+        Self {
+            uv,
+            ddx: Vec2::ZERO,
+            ddy: Vec2::ZERO,
+        }
+    }
+}
+
+// 纹理像素的颜色空间
+// glTF中基础颜色和自发光纹理为sRGB编码，法线、金属度/粗糙度和遮蔽纹理为线性数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    Srgb,
+    #[default]
+    Linear,
+}
+
+// 纹理创建错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextureError {
+    // 宽或高为0
+    EmptyImage { width: u32, height: u32 },
+    // 像素数据与宽高不符
+    PixelCount { expected: usize, actual: usize },
+}
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyImage { width, height } => {
+                write!(f, "empty image ({}x{})", width, height)
+            }
+            Self::PixelCount { expected, actual } => {
+                write!(f, "expected {} pixels, got {}", expected, actual)
+            }
+        }
+    }
+}
+impl std::error::Error for TextureError {}
+
+// mipmap层级
+#[derive(Debug, Clone)]
+pub struct MipLevel {
+    pub width: u32,
+    pub height: u32,
+    // 线性RGBA颜色，按行排列
+    pub data: Vec<Vec4>,
+}
+
 #[derive(Debug)]
 pub struct Texture {
     pub id: usize,
     pub width: u32,
     pub height: u32,
+    // 原始图像的像素格式
     pub format: Format,
-    pub data: Vec<u8>,
+    pub color_space: ColorSpace,
     pub sampler: Sampler,
+    // mipmap链，第0层为原始图像，之后每层宽高减半，直到1x1
+    pub levels: Vec<MipLevel>,
 }
 impl Texture {
-    pub fn sample(&self, mut texcoord: Vec2) -> Color {
-        if self.sampler.wrap_s != WrappingMode::Repeat
-            || self.sampler.wrap_t != WrappingMode::Repeat
-        {
-            panic!("Unsupported texture wrap mode: {:?}", self.sampler.wrap_s)
+    // 解码像素数据并生成mipmap，宽高为0或像素数量与宽高不符时返回错误
+    pub fn new(
+        id: usize,
+        width: u32,
+        height: u32,
+        format: Format,
+        color_space: ColorSpace,
+        pixels: &[u8],
+        sampler: Sampler,
+    ) -> Result<Self, TextureError> {
+        if width == 0 || height == 0 {
+            return Err(TextureError::EmptyImage { width, height });
         }
-        if texcoord.x > 1.0 {
-            texcoord.x -= texcoord.x.floor();
+        let data = decode_pixels(format, color_space, pixels);
+        let expected = width as usize * height as usize;
+        if data.len() != expected {
+            return Err(TextureError::PixelCount {
+                expected,
+                actual: data.len(),
+            });
         }
-        if texcoord.y > 1.0 {
-            texcoord.y -= texcoord.y.floor();
+        let mut texture = Self {
+            id,
+            width,
+            height,
+            format,
+            color_space,
+            sampler,
+            levels: vec![MipLevel {
+                width,
+                height,
+                data,
+            }],
+        };
+        texture.generate_mipmaps();
+        Ok(texture)
+    }
+
+    // 由上一层2x2像素取平均生成mipmap链
+    pub fn generate_mipmaps(&mut self) {
+        self.levels.truncate(1);
+        let (mut width, mut height) = (self.width, self.height);
+        while width > 1 || height > 1 {
+            let prev = self.levels.last().unwrap();
+            width = (width / 2).max(1);
+            height = (height / 2).max(1);
+            let mut data = Vec::with_capacity((width * height) as usize);
+            for y in 0..height {
+                for x in 0..width {
+                    // 奇数尺寸时最后一行（列）与前一行（列）重复采样
+                    let x0 = (2 * x).min(prev.width - 1);
+                    let x1 = (2 * x + 1).min(prev.width - 1);
+                    let y0 = (2 * y).min(prev.height - 1);
+                    let y1 = (2 * y + 1).min(prev.height - 1);
+                    let texel = |x: u32, y: u32| prev.data[(y * prev.width + x) as usize];
+                    let sum = texel(x0, y0) + texel(x1, y0) + texel(x0, y1) + texel(x1, y1);
+                    data.push(sum * 0.25);
+                }
+            }
+            self.levels.push(MipLevel {
+                width,
+                height,
+                data,
+            });
         }
-        let x = (texcoord.x * (self.width - 1) as f32) as usize;
-        let y = (texcoord.y * (self.height - 1) as f32) as usize;
+    }
+
+    // 第level层，超出层级数量时取最后一层
+    pub fn level(&self, level: usize) -> &MipLevel {
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+
+    // 层级数量（包括第0层）
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
 
-        let index = if self.format == Format::R8G8B8 {
-            (y * self.width as usize + x) * 3
-        } else if self.format == Format::R8G8B8A8 {
-            (y * self.width as usize + x) * 4
+    // 根据纹理坐标在屏幕空间的变化率计算mipmap层级（小于等于0为放大）
+    pub fn lod(&self, ddx: Vec2, ddy: Vec2) -> f32 {
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        let ddx = Vec2::new(ddx.x * size.x, ddx.y * size.y);
+        let ddy = Vec2::new(ddy.x * size.x, ddy.y * size.y);
+        let rho = ddx.length().max(ddy.length());
+        if rho > 0.0 {
+            rho.log2()
         } else {
-            panic!("Unsupported texture format: {:?}", self.format);
-        };
-        Color::new(
-            self.data[index] as f32 / 255.,
-            self.data[index + 1] as f32 / 255.,
-            self.data[index + 2] as f32 / 255.,
-        )
+            f32::NEG_INFINITY
+        }
+    }
+
+    pub fn sample(&self, texcoord: TexCoord) -> Color {
+        Color::from_vec4(self.sample_rgba(texcoord))
+    }
+
+    // 采样线性RGBA颜色
+    pub fn sample_rgba(&self, texcoord: TexCoord) -> Vec4 {
+        let uv = texcoord.uv;
+        let lod = self.lod(texcoord.ddx, texcoord.ddy);
+
+        // 放大
+        if lod <= 0.0 {
+            return match self.sampler.mag_filter {
+                Some(MagFilter::Nearest) => self.sample_nearest(0, uv),
+                Some(MagFilter::Linear) | None => self.sample_linear(0, uv),
+            };
+        }
+
+        // 缩小，未指定时使用三线性过滤
+        let max_level = (self.level_count() - 1) as f32;
+        let lod = lod.min(max_level);
+        match self.sampler.min_filter {
+            Some(MinFilter::Nearest) => self.sample_nearest(0, uv),
+            Some(MinFilter::Linear) => self.sample_linear(0, uv),
+            Some(MinFilter::NearestMipmapNearest) => self.sample_nearest(lod.round() as usize, uv),
+            Some(MinFilter::LinearMipmapNearest) => self.sample_linear(lod.round() as usize, uv),
+            Some(MinFilter::NearestMipmapLinear) => {
+                let level = lod.floor() as usize;
+                let t = lod.fract();
+                self.sample_nearest(level, uv)
+                    .lerp(self.sample_nearest(level + 1, uv), t)
+            }
+            Some(MinFilter::LinearMipmapLinear) | None => {
+                let level = lod.floor() as usize;
+                let t = lod.fract();
+                self.sample_linear(level, uv)
+                    .lerp(self.sample_linear(level + 1, uv), t)
+            }
+        }
+    }
+
+    // 最近邻采样
+    pub fn sample_nearest(&self, level: usize, uv: Vec2) -> Vec4 {
+        let MipLevel { width, height, .. } = *self.level(level);
+        let x = (uv.x * width as f32).floor() as i64;
+        let y = (uv.y * height as f32).floor() as i64;
+        self.texel(level, x, y)
+    }
+
+    // 双线性采样（以像素中心为采样点）
+    pub fn sample_linear(&self, level: usize, uv: Vec2) -> Vec4 {
+        let MipLevel { width, height, .. } = *self.level(level);
+        let x = uv.x * width as f32 - 0.5;
+        let y = uv.y * height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = self
+            .texel(level, x0, y0)
+            .lerp(self.texel(level, x0 + 1, y0), tx);
+        let bottom = self
+            .texel(level, x0, y0 + 1)
+            .lerp(self.texel(level, x0 + 1, y0 + 1), tx);
+        top.lerp(bottom, ty)
     }
+
+    // 读取第level层(x, y)处的像素，超出范围的坐标按采样器的环绕模式处理
+    pub fn texel(&self, level: usize, x: i64, y: i64) -> Vec4 {
+        let level = self.level(level);
+        let x = wrap(x, level.width, self.sampler.wrap_s);
+        let y = wrap(y, level.height, self.sampler.wrap_t);
+        level.data[y * level.width as usize + x]
+    }
+}
+
+// 将图像像素解码为线性RGBA颜色
+// 整数格式归一化到[0, 1]，浮点格式保持原值（可以大于1，如HDR纹理）
+// sRGB纹理的整数格式颜色通道转换到线性空间（透明度和浮点格式本身就是线性的）
+// 单通道按灰度处理(r, r, r, 1)，双通道按灰度+透明度处理(r, r, r, g)
+pub fn decode_pixels(format: Format, color_space: ColorSpace, pixels: &[u8]) -> Vec<Vec4> {
+    let (channel_count, channel_size) = match format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+    let channel = |bytes: &[u8]| match channel_size {
+        1 => bytes[0] as f32 / u8::MAX as f32,
+        2 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32,
+        _ => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    };
+    let srgb = color_space == ColorSpace::Srgb && channel_size < 4;
+    let color = |c: f32| if srgb { srgb_to_linear(c) } else { c };
+    pixels
+        .chunks_exact(channel_count * channel_size)
+        .map(|pixel| {
+            let c: Vec<f32> = pixel.chunks_exact(channel_size).map(channel).collect();
+            match channel_count {
+                1 => Vec4::new(color(c[0]), color(c[0]), color(c[0]), 1.0),
+                2 => Vec4::new(color(c[0]), color(c[0]), color(c[0]), c[1]),
+                3 => Vec4::new(color(c[0]), color(c[1]), color(c[2]), 1.0),
+                _ => Vec4::new(color(c[0]), color(c[1]), color(c[2]), c[3]),
+            }
+        })
+        .collect()
+}
+
+// 将像素坐标按环绕模式映射到[0, size)，size不能为0
+pub fn wrap(coord: i64, size: u32, mode: WrappingMode) -> usize {
+    let size = size as i64;
+    let coord = match mode {
+        WrappingMode::Repeat => coord.rem_euclid(size),
+        WrappingMode::ClampToEdge => coord.clamp(0, size - 1),
+        // 以2 * size为周期，后半个周期镜像
+        WrappingMode::MirroredRepeat => {
+            let coord = coord.rem_euclid(2 * size);
+            if coord < size {
+                coord
+            } else {
+                2 * size - 1 - coord
+            }
+        }
+    };
+    coord as usize
 }
 
 #[derive(Debug, Default)]
 pub struct TextureStorage {
     pub texture_id_map: HashMap<usize, Texture>,
 }
+impl TextureStorage {
+    // 纹理id或纹理坐标不存在时返回None
+    pub fn sample(&self, texture_id: Option<usize>, texcoord: Option<TexCoord>) -> Option<Color> {
+        let texture = self.texture_id_map.get(&texture_id?)?;
+        Some(texture.sample(texcoord?))
+    }
+}
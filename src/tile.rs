@@ -0,0 +1,118 @@
+use crate::{blend::BlendState, color::Color, oit::FragmentLists};
+
+// 分块光栅化时每个块的边长（像素）
+pub const TILE_SIZE: u32 = 64;
+
+// 屏幕上的一块矩形区域及其颜色缓冲、深度缓冲和逐像素链表
+// 缓冲布局与Renderer相同：以区域左下角为原点逐行存储，每个像素的采样点连续存储
+#[derive(Debug, Clone)]
+pub struct Tile {
+    // 区域左下角的屏幕坐标
+    pub x: i32,
+    pub y: i32,
+    // 区域的宽高
+    pub width: u32,
+    pub height: u32,
+    // 每像素采样数
+    pub sample_count: usize,
+    // 颜色缓冲（RGBA8）
+    pub color_buffer: Vec<u8>,
+    pub depth_buffer: Vec<f32>,
+    pub fragment_lists: FragmentLists,
+}
+impl Tile {
+    pub fn new(x: i32, y: i32, width: u32, height: u32, sample_count: usize) -> Self {
+        let pixel_count = (width * height) as usize;
+        Self {
+            x,
+            y,
+            width,
+            height,
+            sample_count,
+            color_buffer: vec![0; pixel_count * sample_count * 4],
+            depth_buffer: vec![f32::MIN; pixel_count * sample_count],
+            fragment_lists: FragmentLists::new(pixel_count),
+        }
+    }
+
+    // 将区域划分为不超过TILE_SIZE x TILE_SIZE的块（从下到上、从左到右）
+    pub fn split(x: i32, y: i32, width: u32, height: u32, sample_count: usize) -> Vec<Tile> {
+        let mut tiles = Vec::new();
+        for tile_y in (0..height).step_by(TILE_SIZE as usize) {
+            for tile_x in (0..width).step_by(TILE_SIZE as usize) {
+                tiles.push(Tile::new(
+                    x + tile_x as i32,
+                    y + tile_y as i32,
+                    TILE_SIZE.min(width - tile_x),
+                    TILE_SIZE.min(height - tile_y),
+                    sample_count,
+                ));
+            }
+        }
+        tiles
+    }
+
+    // 像素(x, y)（屏幕坐标）在区域中的序号
+    pub fn index(&self, x: i32, y: i32) -> usize {
+        ((y - self.y) * self.width as i32 + (x - self.x)) as usize
+    }
+
+    // 区域内的像素范围(min_x, min_y, max_x, max_y)，max不包含在内
+    pub fn rect(&self) -> (i32, i32, i32, i32) {
+        (
+            self.x,
+            self.y,
+            self.x + self.width as i32,
+            self.y + self.height as i32,
+        )
+    }
+
+    // 写入第index个像素的第sample个采样点
+    pub fn write_sample(&mut self, index: usize, sample: usize, color: Color) {
+        let offset = (index * self.sample_count + sample) * 4;
+        self.color_buffer[offset..offset + 4].copy_from_slice(&color.to_rgba8());
+    }
+
+    pub fn read_sample(&self, index: usize, sample: usize) -> Color {
+        let offset = (index * self.sample_count + sample) * 4;
+        let mut rgba = [0; 4];
+        rgba.copy_from_slice(&self.color_buffer[offset..offset + 4]);
+        Color::from_rgba8(rgba)
+    }
+
+    // 按blend将颜色与采样点已有的颜色混合
+    pub fn blend_sample(&mut self, index: usize, sample: usize, color: Color, blend: &BlendState) {
+        let dst = self.read_sample(index, sample);
+        self.write_sample(index, sample, blend.blend(color, dst));
+    }
+
+    // 该区域第row行在frame中的采样点范围
+    fn frame_row(&self, frame: &Tile, row: u32) -> std::ops::Range<usize> {
+        let start = frame.index(self.x, self.y + row as i32) * self.sample_count;
+        start..start + self.width as usize * self.sample_count
+    }
+
+    // 从包含该区域的frame中拷贝颜色和深度
+    pub fn load(&mut self, frame: &Tile) {
+        let row_len = self.width as usize * self.sample_count;
+        for row in 0..self.height {
+            let src = self.frame_row(frame, row);
+            let dst = row as usize * row_len..(row as usize + 1) * row_len;
+            self.depth_buffer[dst.clone()].copy_from_slice(&frame.depth_buffer[src.clone()]);
+            self.color_buffer[dst.start * 4..dst.end * 4]
+                .copy_from_slice(&frame.color_buffer[src.start * 4..src.end * 4]);
+        }
+    }
+
+    // 将颜色和深度写回包含该区域的frame
+    pub fn store(&self, frame: &mut Tile) {
+        let row_len = self.width as usize * self.sample_count;
+        for row in 0..self.height {
+            let dst = self.frame_row(frame, row);
+            let src = row as usize * row_len..(row as usize + 1) * row_len;
+            frame.depth_buffer[dst.clone()].copy_from_slice(&self.depth_buffer[src.clone()]);
+            frame.color_buffer[dst.start * 4..dst.end * 4]
+                .copy_from_slice(&self.color_buffer[src.start * 4..src.end * 4]);
+        }
+    }
+}
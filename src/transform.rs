@@ -1,4 +1,4 @@
-use crate::math::{Mat4, Quat, Vec3, Vec4};
+use crate::math::{Mat3, Mat4, Quat, Vec3, Vec4};
 
 // 缩放矩阵
 pub fn scale_mat4(scale: Vec3) -> Mat4 {
@@ -60,7 +60,41 @@ pub fn translation_mat4(translation: Vec3) -> Mat4 {
     }
 }
 
+// 法线矩阵：模型矩阵左上角3x3部分的逆转置，非均匀缩放后法线仍与表面垂直
+// 切线等表面上的方向向量直接用模型矩阵的3x3部分变换
+// 模型矩阵不可逆（如某个方向缩放为0）时退化为3x3部分本身
+pub fn normal_matrix(model_transformation: Mat4) -> Mat3 {
+    let linear = Mat3::from_mat4(model_transformation);
+    linear
+        .try_inverse()
+        .map_or(linear, |inverse| inverse.transpose())
+}
+
 // 旋转
 pub fn rotation_quat(axis: Vec3, angle: f32) -> Quat {
     Quat::from_axis_angle(axis, angle)
 }
+
+// 平移、旋转、缩放变换（按缩放->旋转->平移的顺序作用）
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn to_mat4(&self) -> Mat4 {
+        translation_mat4(self.translation) * self.rotation.to_mat4() * scale_mat4(self.scale)
+    }
+}
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
@@ -1,4 +1,6 @@
-use rand::Rng;
+use std::cell::{Cell, RefCell};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
     color::Color,
@@ -7,22 +9,51 @@ use crate::{
     texture::TextureStorage,
 };
 
+// 上下翻转帧缓冲，每个像素的字节数由缓冲区大小推出（RGB8、RGBA8均可）
 pub fn flip_vertically(frame_buffer: &Vec<u8>, width: usize, height: usize) -> Vec<u8> {
     let mut flipped_frame_buffer = frame_buffer.clone();
+    if width == 0 || height == 0 {
+        return flipped_frame_buffer;
+    }
+    let row = frame_buffer.len() / height;
     for y in 0..height / 2 {
-        for x in 0..width {
-            let top_index = (y * width + x) * 3;
-            let bottom_index = ((height - y - 1) * width + x) * 3;
-            flipped_frame_buffer.swap(top_index, bottom_index);
-            flipped_frame_buffer.swap(top_index + 1, bottom_index + 1);
-            flipped_frame_buffer.swap(top_index + 2, bottom_index + 2);
-        }
+        let (top, bottom) = flipped_frame_buffer.split_at_mut((height - y - 1) * row);
+        top[y * row..(y + 1) * row].swap_with_slice(&mut bottom[..row]);
     }
     flipped_frame_buffer
 }
 
+thread_local! {
+    // 设置种子后使用的确定性随机数生成器（每个线程独立）
+    static SEEDED_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+    // 当前线程设置的种子
+    static SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+// 设置当前线程随机颜色的种子，使渲染结果可复现（None恢复为非确定性随机）
+pub fn seed_rand_color(seed: Option<u64>) {
+    SEED.set(seed);
+    SEEDED_RNG.with(|rng| *rng.borrow_mut() = seed.map(StdRng::seed_from_u64));
+}
+
 pub fn rand_color() -> Color {
-    let mut rng = rand::thread_rng();
+    SEEDED_RNG.with(|seeded_rng| match seeded_rng.borrow_mut().as_mut() {
+        Some(rng) => gen_color(rng),
+        None => gen_color(&mut rand::thread_rng()),
+    })
+}
+
+// 第index个顶点的随机颜色，设置种子后只由种子和index决定，与生成的顺序无关
+pub fn indexed_rand_color(index: u64) -> Color {
+    match SEED.get() {
+        Some(seed) => gen_color(&mut StdRng::seed_from_u64(
+            seed ^ index.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        )),
+        None => rand_color(),
+    }
+}
+
+fn gen_color<R: Rng>(rng: &mut R) -> Color {
     Color::new(
         rng.gen_range(0.0..=1.0),
         rng.gen_range(0.0..=1.0),
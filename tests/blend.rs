@@ -0,0 +1,57 @@
+use tiny_renderer::{
+    blend::{BlendFactor, BlendOperation, BlendState},
+    color::Color,
+};
+
+fn assert_color(actual: Color, expected: Color) {
+    let diff = (actual.to_vec4() - expected.to_vec4()).length();
+    assert!(diff < 1e-5, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn alpha_blending_over_operator() {
+    let src = Color::rgba(1.0, 0.0, 0.0, 0.25);
+    let dst = Color::new(0.0, 0.0, 1.0);
+    let color = BlendState::ALPHA_BLENDING.blend(src, dst);
+    assert_color(color, Color::rgba(0.25, 0.0, 0.75, 1.0));
+
+    // 完全不透明的片段覆盖帧缓冲
+    let color = BlendState::default().blend(Color::GREEN, dst);
+    assert_color(color, Color::GREEN);
+}
+
+#[test]
+fn additive_blending_saturates() {
+    let src = Color::rgba(0.8, 0.5, 0.0, 0.5);
+    let dst = Color::rgba(0.8, 0.1, 0.2, 0.0);
+    let color = BlendState::ADDITIVE.blend(src, dst);
+    assert_color(color, Color::rgba(1.0, 0.35, 0.2, 0.5));
+}
+
+#[test]
+fn separate_color_and_alpha_operations() {
+    let state = BlendState {
+        color_src_factor: BlendFactor::One,
+        color_dst_factor: BlendFactor::One,
+        color_operation: BlendOperation::ReverseSubtract,
+        alpha_src_factor: BlendFactor::Zero,
+        alpha_dst_factor: BlendFactor::One,
+        alpha_operation: BlendOperation::Max,
+    };
+    let src = Color::rgba(0.25, 0.5, 1.0, 0.2);
+    let dst = Color::rgba(0.5, 0.5, 0.5, 0.6);
+    // dst - src，小于0时截断；透明度取较大值
+    assert_color(state.blend(src, dst), Color::rgba(0.25, 0.0, 0.0, 0.6));
+}
+
+#[test]
+fn rgba8_round_trip() {
+    // 颜色通道编码为sRGB，透明度保持线性
+    let color = Color::rgba(1.0, 0.0, 0.2, 0.6);
+    assert_eq!(color.to_rgba8(), [255, 0, 124, 153]);
+    assert_eq!(Color::new(0.5, 0.5, 0.5).to_rgba8(), [188, 188, 188, 255]);
+    for c in 0..=255 {
+        let rgba = [c, c, c, c];
+        assert_eq!(Color::from_rgba8(rgba).to_rgba8(), rgba);
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{camera, colored_mesh, render, vertex};
+use tiny_renderer::{
+    camera::Camera,
+    color::Color,
+    math::Vec3,
+    renderer::{ClipVertex, Renderer, RendererSettings},
+};
+
+const SIZE: u32 = 16;
+
+// 位于原点、看向-z的相机
+fn camera_at_origin(near: f32) -> Camera {
+    camera(Vec3::ZERO, near, 90.0, 1.0)
+}
+
+// 在16x16的视口中渲染白色三角形
+fn render_triangle(positions: [Vec3; 3]) -> Renderer {
+    let settings = RendererSettings {
+        vertex_color_interp: true,
+        ..Default::default()
+    };
+    render(
+        camera_at_origin(0.1),
+        SIZE,
+        settings,
+        vec![colored_mesh(&positions, Color::WHITE)],
+    )
+}
+
+// 被写入的像素数
+fn covered_pixels(positions: [Vec3; 3]) -> usize {
+    render_triangle(positions)
+        .frame_buffer
+        .chunks(4)
+        .filter(|rgba| rgba[0] == 255)
+        .count()
+}
+
+#[test]
+fn back_face_cull_uses_the_eye_direction() {
+    // 画面左侧的斜墙，法线(1, 0, -0.2)朝向相机，但与-z方向的夹角小于90°
+    let a = Vec3::new(-1.8, -1.0, -2.0);
+    let b = Vec3::new(-2.2, -1.0, -4.0);
+    let c = Vec3::new(-1.8, 1.0, -2.0);
+    assert!(covered_pixels([a, b, c]) > 0);
+    // 反向的三角形背对相机
+    assert_eq!(covered_pixels([a, c, b]), 0);
+}
+
+#[test]
+fn depth_matches_the_projected_surface() {
+    // 平面z = -2 - x上的三角形
+    let renderer = render_triangle([
+        Vec3::new(-1.0, -1.0, -1.0),
+        Vec3::new(1.0, -3.0, -3.0),
+        Vec3::new(0.0, 2.0, -2.0),
+    ]);
+
+    let projection = camera_at_origin(0.1)
+        .frustum
+        .persp_projection_transformation();
+    let mut covered = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let depth = renderer.depth_buffer[(y * SIZE + x) as usize];
+            if depth == f32::MIN {
+                continue;
+            }
+            covered += 1;
+            // 像素中心的视线与平面的交点：x_view = x_ndc * -z，代入平面方程
+            let ndc_x = x as f32 * 2.0 / (SIZE - 1) as f32 - 1.0;
+            let ndc_y = y as f32 * 2.0 / (SIZE - 1) as f32 - 1.0;
+            let z = -2.0 / (1.0 - ndc_x);
+            let p = projection * Vec3::new(-ndc_x * z, -ndc_y * z, z).extend(1.0);
+            let expected = p.z / p.w;
+            assert!(
+                (depth - expected).abs() < 1e-4,
+                "({}, {}): {} != {}",
+                x,
+                y,
+                depth,
+                expected
+            );
+        }
+    }
+    assert!(covered > 0);
+}
+
+#[test]
+fn triangle_straddling_the_near_plane_is_clipped_at_the_near_plane() {
+    // 两个顶点在相机前方，一个在相机后方
+    let mut triangle = [
+        Vec3::new(-1.0, -1.0, -5.0),
+        Vec3::new(1.0, -1.0, -5.0),
+        Vec3::new(0.0, 1.0, 5.0),
+    ]
+    .map(|p| vertex(p, Color::WHITE));
+    let mut renderer = common::renderer(
+        camera_at_origin(1.0),
+        SIZE,
+        SIZE,
+        RendererSettings::default(),
+    );
+    renderer.apply_view_transformation(&mut triangle);
+    let view_space_positions = triangle.map(|v| v.position.to_cartesian_point());
+    renderer.apply_projection_transformation(&mut triangle);
+    let polygon = Renderer::homogeneous_clip(
+        (0..3)
+            .map(|i| ClipVertex {
+                vertex: triangle[i],
+                world_position: view_space_positions[i],
+                view_space_position: view_space_positions[i],
+            })
+            .collect(),
+    );
+
+    // 保留两个原顶点，另外两个顶点落在近平面上
+    assert_eq!(polygon.len(), 4);
+    let mut on_near_plane = 0;
+    for v in polygon {
+        let p = v.vertex.position;
+        // 裁剪空间的w为到相机的距离
+        assert!((p.w + v.view_space_position.z).abs() < 1e-5, "{:?}", v);
+        if v.view_space_position.z > -5.0 + 1e-5 {
+            on_near_plane += 1;
+            assert!((v.view_space_position.z + 1.0).abs() < 1e-5, "{:?}", v);
+            assert!((p.w - 1.0).abs() < 1e-5, "{:?}", v);
+            // 近平面映射到NDC z = 1
+            assert!((p.z / p.w - 1.0).abs() < 1e-5, "{:?}", v);
+        }
+    }
+    assert_eq!(on_near_plane, 2);
+}
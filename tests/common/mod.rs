@@ -0,0 +1,51 @@
+// 集成测试共用的相机、渲染器和网格构造
+// 每个测试只用到其中一部分
+#![allow(dead_code)]
+
+use tiny_renderer::{
+    camera::Camera,
+    color::Color,
+    math::Vec3,
+    mesh::{Mesh, Vertex},
+    renderer::{Renderer, RendererSettings, Viewport},
+    scene::Scene,
+    texture::TextureStorage,
+};
+
+// 位于position、看向-z方向的相机，fov为角度，远平面为100
+pub fn camera(position: Vec3, near: f32, fov: f32, aspect: f32) -> Camera {
+    Camera::new(near, 100.0, aspect, fov.to_radians(), position)
+}
+
+// 视口左下角在原点的渲染器
+pub fn renderer(camera: Camera, width: u32, height: u32, settings: RendererSettings) -> Renderer {
+    Renderer::new(camera, Viewport::new(0, 0, width, height), settings)
+}
+
+// 在size x size的视口中渲染网格（没有光源和纹理）
+pub fn render(
+    camera: Camera,
+    size: u32,
+    settings: RendererSettings,
+    meshes: Vec<Mesh>,
+) -> Renderer {
+    let mut renderer = renderer(camera, size, size, settings);
+    renderer.draw_scene(&Scene::from_meshes(meshes), &[], &TextureStorage::default());
+    renderer
+}
+
+pub fn vertex(position: Vec3, color: Color) -> Vertex {
+    Vertex {
+        position: position.extend(1.0),
+        color: Some(color),
+        ..Default::default()
+    }
+}
+
+// 顶点颜色相同的网格（没有索引缓冲，每三个顶点组成一个三角形）
+pub fn colored_mesh(positions: &[Vec3], color: Color) -> Mesh {
+    Mesh {
+        vertices: positions.iter().map(|&p| vertex(p, color)).collect(),
+        ..Default::default()
+    }
+}
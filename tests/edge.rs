@@ -0,0 +1,93 @@
+mod common;
+
+use common::{camera, colored_mesh, render};
+use tiny_renderer::{
+    color::Color,
+    edge::{TriangleEdges, SUBPIXEL_SCALE},
+    math::{Vec2, Vec3},
+    renderer::{Projection, RendererSettings},
+};
+
+// 像素中心p处覆盖它的三角形个数
+fn coverage_count(triangles: &[[Vec2; 3]], p: Vec2) -> usize {
+    let p = (
+        (p.x * SUBPIXEL_SCALE as f32) as i64,
+        (p.y * SUBPIXEL_SCALE as f32) as i64,
+    );
+    triangles
+        .iter()
+        .filter_map(|triangle| TriangleEdges::new(*triangle))
+        .filter(|edges| edges.covers(edges.evaluate(p)))
+        .count()
+}
+
+#[test]
+fn shared_edges_are_covered_exactly_once() {
+    // 以正方形内部的点为中心的三角扇（包含顺时针的三角形），边经过像素中心
+    let corners = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(4.0, 0.0),
+        Vec2::new(8.0, 0.0),
+        Vec2::new(8.0, 8.0),
+        Vec2::new(4.0, 8.0),
+        Vec2::new(0.0, 8.0),
+    ];
+    for center in [Vec2::new(4.0, 4.0), Vec2::new(3.3, 5.7)] {
+        let triangles: Vec<[Vec2; 3]> = (0..corners.len())
+            .map(|i| {
+                let next = corners[(i + 1) % corners.len()];
+                if i % 2 == 0 {
+                    [center, corners[i], next]
+                } else {
+                    [center, next, corners[i]]
+                }
+            })
+            .collect();
+        for y in 0..=8 {
+            for x in 0..=8 {
+                let p = Vec2::new(x as f32, y as f32);
+                // 正方形的左边和上边属于正方形，右边和下边不属于
+                let expected = usize::from(x < 8 && y > 0);
+                assert_eq!(coverage_count(&triangles, p), expected, "{:?}", p);
+            }
+        }
+    }
+}
+
+#[test]
+fn degenerate_triangles_cover_nothing() {
+    let line = [Vec2::ZERO, Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0)];
+    assert!(TriangleEdges::new(line).is_none());
+}
+
+#[test]
+fn quad_diagonal_has_no_cracks() {
+    // 正交投影下世界坐标[-1, 1]映射到屏幕坐标[0, 16]，正方形的顶点和对角线都经过像素中心
+    let (a, b, c, d) = (
+        Vec3::new(-0.5, -0.5, 0.0),
+        Vec3::new(0.5, -0.5, 0.0),
+        Vec3::new(0.5, 0.5, 0.0),
+        Vec3::new(-0.5, 0.5, 0.0),
+    );
+    let mesh = colored_mesh(&[a, b, c, a, c, d], Color::WHITE);
+    let settings = RendererSettings {
+        projection: Projection::Orthographic,
+        vertex_color_interp: true,
+        ..Default::default()
+    };
+    let renderer = render(
+        camera(Vec3::new(0.0, 0.0, 3.0), 1.0, 90.0, 1.0),
+        17,
+        settings,
+        vec![mesh],
+    );
+
+    for y in 0..17 {
+        for x in 0..17 {
+            let covered = renderer.frame_buffer[(y * 17 + x) * 4] == 255;
+            // 左上填充规则：包含左边x = 4和上边y = 12，不包含右边和下边
+            let expected = (4..12).contains(&x) && (5..13).contains(&y);
+            assert_eq!(covered, expected, "({}, {})", x, y);
+        }
+    }
+}
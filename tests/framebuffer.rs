@@ -0,0 +1,97 @@
+use std::io;
+
+use tiny_renderer::framebuffer::FrameBuffer;
+
+const WIDTH: u32 = 2;
+const HEIGHT: u32 = 3;
+
+// 每个像素的颜色都不同，(x, y)以左下角为原点
+fn pixel(x: u32, y: u32) -> [u8; 4] {
+    [
+        (x * 10 + 1) as u8,
+        (y * 10 + 2) as u8,
+        (100 + x + y) as u8,
+        (200 + y) as u8,
+    ]
+}
+
+fn frame() -> FrameBuffer {
+    let mut data = Vec::new();
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            data.extend_from_slice(&pixel(x, y));
+        }
+    }
+    FrameBuffer::new(WIDTH, HEIGHT, data)
+}
+
+// 按从上到下的行顺序排列的像素
+fn top_down_pixels() -> Vec<[u8; 4]> {
+    (0..HEIGHT)
+        .rev()
+        .flat_map(|y| (0..WIDTH).map(move |x| pixel(x, y)))
+        .collect()
+}
+
+#[test]
+fn ppm_is_written_top_down() {
+    let mut bytes = Vec::new();
+    frame().write_ppm(&mut bytes).unwrap();
+    let header = b"P6\n2 3\n255\n";
+    assert_eq!(&bytes[..header.len()], header);
+
+    // 不包含透明度通道
+    let expected: Vec<u8> = top_down_pixels()
+        .iter()
+        .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+        .collect();
+    assert_eq!(&bytes[header.len()..], &expected[..]);
+}
+
+#[test]
+fn tga_is_written_bottom_up_as_bgra() {
+    let mut bytes = Vec::new();
+    frame().write_tga(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 18 + (WIDTH * HEIGHT * 4) as usize);
+    // 未压缩真彩色
+    assert_eq!(bytes[2], 2);
+    assert_eq!(u16::from_le_bytes([bytes[12], bytes[13]]), WIDTH as u16);
+    assert_eq!(u16::from_le_bytes([bytes[14], bytes[15]]), HEIGHT as u16);
+    assert_eq!(bytes[16], 32);
+    // 8位透明度，第5位为0表示原点在左下角
+    assert_eq!(bytes[17], 8);
+
+    let expected: Vec<u8> = (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| pixel(x, y)))
+        .flat_map(|rgba| [rgba[2], rgba[1], rgba[0], rgba[3]])
+        .collect();
+    assert_eq!(&bytes[18..], &expected[..]);
+}
+
+#[test]
+fn tga_rejects_sizes_that_do_not_fit_in_16_bits() {
+    let frame = FrameBuffer::new(65536, 1, vec![0; 65536 * 4]);
+    let err = frame.write_tga(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let frame = FrameBuffer::new(65535, 1, vec![0; 65535 * 4]);
+    assert!(frame.write_tga(&mut Vec::new()).is_ok());
+}
+
+#[test]
+fn png_round_trip() {
+    let mut bytes = Vec::new();
+    frame().write_png(&mut bytes).unwrap();
+
+    let decoder = png::Decoder::new(&bytes[..]);
+    let mut reader = decoder.read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height), (WIDTH, HEIGHT));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(info.bit_depth, png::BitDepth::Eight);
+
+    // png的第一行为图像顶部
+    let expected: Vec<u8> = top_down_pixels().concat();
+    assert_eq!(&data[..info.buffer_size()], &expected[..]);
+}
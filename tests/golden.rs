@@ -0,0 +1,416 @@
+// 渲染结果与参考图片逐像素比较的回归测试
+//
+// 更新参考图片：UPDATE_GOLDEN=1 cargo test --test golden
+use std::{fs::File, path::PathBuf};
+
+use gltf::material::AlphaMode;
+use tiny_renderer::{
+    camera::Camera,
+    framebuffer::FrameBuffer,
+    light::{AmbientLight, DirectionalLight, Light, PointLight, SpotLight},
+    loader::load_glft,
+    math::Vec3,
+    msaa::SampleCount,
+    renderer::{Renderer, RendererSettings, Viewport},
+    scene::Scene as Model,
+    shader::{pbr_shader, phong_shader, FragmentShader},
+    shadow::ShadowSettings,
+    util::seed_rand_color,
+};
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+const SEED: u64 = 42;
+// 单个通道允许的误差
+const CHANNEL_TOLERANCE: u8 = 2;
+// 允许超出误差的像素比例
+const MAX_MISMATCH_RATIO: f32 = 0.001;
+
+struct Scene {
+    model: &'static str,
+    camera_position: Vec3,
+    camera_target: Vec3,
+    settings: RendererSettings,
+    shader: fn() -> FragmentShader,
+    lights: Vec<Light>,
+}
+
+fn default_lights() -> Vec<Light> {
+    vec![
+        Light::Ambient(AmbientLight { intensity: 0.2 }),
+        Light::Point(PointLight {
+            position: Vec3::new(-5.0, 5.0, 5.0),
+            intensity: 100.0,
+        }),
+    ]
+}
+
+fn fragment_shading() -> RendererSettings {
+    RendererSettings {
+        fragment_shading: true,
+        ..Default::default()
+    }
+}
+
+fn vertex_color_interp() -> RendererSettings {
+    RendererSettings {
+        vertex_color_interp: true,
+        ..Default::default()
+    }
+}
+
+// edit在渲染前修改加载的模型（如材质）
+fn render(scene: &Scene, edit: impl Fn(&mut Model)) -> FrameBuffer {
+    seed_rand_color(Some(SEED));
+    let (mut model, texture_storage) = load_glft(scene.model).unwrap();
+    edit(&mut model);
+
+    let mut camera = Camera::new(
+        0.1,
+        100.0,
+        WIDTH as f32 / HEIGHT as f32,
+        60.0f32.to_radians(),
+        scene.camera_position,
+    );
+    camera.look_at(scene.camera_target, Vec3::Y);
+
+    let viewport = Viewport::new(0, 0, WIDTH, HEIGHT);
+    let mut renderer = Renderer::new(camera, viewport, scene.settings);
+    renderer.fragment_shader = Some((scene.shader)());
+    renderer.draw_scene(&model, &scene.lights, &texture_storage);
+    renderer.color_frame_buffer()
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.png", name))
+}
+
+fn read_png(path: &PathBuf) -> FrameBuffer {
+    let decoder = png::Decoder::new(File::open(path).unwrap());
+    let mut reader = decoder.read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    data.truncate(info.buffer_size());
+    // png按从上到下存储，FrameBuffer以左下角为原点
+    let top_down = FrameBuffer::new(info.width, info.height, data);
+    FrameBuffer::new(info.width, info.height, top_down.top_down_data())
+}
+
+fn assert_golden(name: &str, scene: Scene) {
+    assert_golden_with(name, scene, |_| {});
+}
+
+fn assert_golden_with(name: &str, scene: Scene, edit: impl Fn(&mut Model)) {
+    let actual = render(&scene, edit);
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save(&path).unwrap();
+        return;
+    }
+    let expected = read_png(&path);
+    assert_eq!(
+        (actual.width, actual.height),
+        (expected.width, expected.height)
+    );
+
+    // 超出误差的像素标记为红色，其余像素变暗显示
+    let mut mismatches = 0;
+    let mut diff = Vec::with_capacity(actual.data.len());
+    for (a, e) in actual.data.chunks(4).zip(expected.data.chunks(4)) {
+        if a.iter()
+            .zip(e)
+            .any(|(a, e)| a.abs_diff(*e) > CHANNEL_TOLERANCE)
+        {
+            mismatches += 1;
+            diff.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let gray = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 9) as u8;
+            diff.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+
+    let pixel_count = (WIDTH * HEIGHT) as f32;
+    if mismatches as f32 / pixel_count > MAX_MISMATCH_RATIO {
+        let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let actual_path = out_dir.join(format!("{}-actual.png", name));
+        let diff_path = out_dir.join(format!("{}-diff.png", name));
+        actual.save(&actual_path).unwrap();
+        FrameBuffer::new(WIDTH, HEIGHT, diff)
+            .save(&diff_path)
+            .unwrap();
+        panic!(
+            "{}: {} of {} pixels differ from {:?}\n  actual: {:?}\n  diff: {:?}",
+            name, mismatches, pixel_count, path, actual_path, diff_path
+        );
+    }
+}
+
+#[test]
+fn cube() {
+    assert_golden(
+        "cube",
+        Scene {
+            model: "assets/cube/cube.gltf",
+            camera_position: Vec3::new(2.0, 3.0, 4.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn cube_vertex_color() {
+    assert_golden(
+        "cube_vertex_color",
+        Scene {
+            model: "assets/cube/cube.gltf",
+            camera_position: Vec3::new(2.0, 3.0, 4.0),
+            camera_target: Vec3::ZERO,
+            settings: vertex_color_interp(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn monkey() {
+    assert_golden(
+        "monkey",
+        Scene {
+            model: "assets/monkey/monkey.gltf",
+            camera_position: Vec3::new(1.0, 1.5, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn sphere() {
+    assert_golden(
+        "sphere",
+        Scene {
+            model: "assets/sphere/sphere.gltf",
+            camera_position: Vec3::new(1.0, 2.0, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn suzanne() {
+    assert_golden(
+        "suzanne",
+        Scene {
+            model: "assets/suzanne/Suzanne.gltf",
+            camera_position: Vec3::new(0.0, 0.5, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn box_textured() {
+    assert_golden(
+        "box_textured",
+        Scene {
+            model: "assets/box-textured/BoxTextured.gltf",
+            camera_position: Vec3::new(1.0, 1.5, 2.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn cornell_box() {
+    assert_golden(
+        "cornell_box",
+        Scene {
+            model: "assets/cornell-box.gltf",
+            camera_position: Vec3::new(0.0, 0.0, 3.5),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn suzanne_msaa() {
+    assert_golden(
+        "suzanne_msaa",
+        Scene {
+            model: "assets/suzanne/Suzanne.gltf",
+            camera_position: Vec3::new(0.0, 0.5, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: RendererSettings {
+                msaa: SampleCount::X4,
+                ..fragment_shading()
+            },
+            shader: phong_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn suzanne_pbr() {
+    assert_golden(
+        "suzanne_pbr",
+        Scene {
+            model: "assets/suzanne/Suzanne.gltf",
+            camera_position: Vec3::new(0.0, 0.5, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: pbr_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn sphere_pbr() {
+    assert_golden(
+        "sphere_pbr",
+        Scene {
+            model: "assets/sphere/sphere.gltf",
+            camera_position: Vec3::new(1.0, 2.0, 3.0),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: pbr_shader,
+            lights: default_lights(),
+        },
+    );
+}
+
+#[test]
+fn cornell_box_multiple_lights() {
+    assert_golden(
+        "cornell_box_multiple_lights",
+        Scene {
+            model: "assets/cornell-box.gltf",
+            camera_position: Vec3::new(0.0, 0.0, 3.5),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: pbr_shader,
+            lights: vec![
+                Light::Ambient(AmbientLight { intensity: 0.05 }),
+                Light::Directional(DirectionalLight {
+                    direction: Vec3::new(0.3, -1.0, -0.5).normalize(),
+                    intensity: 3.0,
+                }),
+                Light::Spot(SpotLight {
+                    position: Vec3::new(0.0, 0.9, 0.5),
+                    direction: Vec3::NEG_Y,
+                    intensity: 8.0,
+                    inner_cone_angle: 20.0f32.to_radians(),
+                    outer_cone_angle: 35.0f32.to_radians(),
+                }),
+                Light::Point(PointLight {
+                    position: Vec3::new(0.5, 0.0, 0.5),
+                    intensity: 1.0,
+                }),
+            ],
+        },
+    );
+}
+
+#[test]
+fn cornell_box_shadows() {
+    assert_golden(
+        "cornell_box_shadows",
+        Scene {
+            model: "assets/cornell-box.gltf",
+            camera_position: Vec3::new(0.0, 0.0, 3.5),
+            camera_target: Vec3::ZERO,
+            settings: RendererSettings {
+                shadow: Some(ShadowSettings::default()),
+                ..fragment_shading()
+            },
+            shader: pbr_shader,
+            lights: vec![
+                Light::Ambient(AmbientLight { intensity: 0.05 }),
+                Light::Directional(DirectionalLight {
+                    direction: Vec3::new(0.3, -1.0, -0.5).normalize(),
+                    intensity: 3.0,
+                }),
+                Light::Spot(SpotLight {
+                    position: Vec3::new(-0.6, 0.9, 0.6),
+                    direction: Vec3::new(0.4, -1.0, -0.1).normalize(),
+                    intensity: 8.0,
+                    inner_cone_angle: 20.0f32.to_radians(),
+                    outer_cone_angle: 35.0f32.to_radians(),
+                }),
+            ],
+        },
+    );
+}
+
+#[test]
+fn cornell_box_point_shadows() {
+    assert_golden(
+        "cornell_box_point_shadows",
+        Scene {
+            model: "assets/cornell-box.gltf",
+            camera_position: Vec3::new(0.0, 0.0, 3.5),
+            camera_target: Vec3::ZERO,
+            settings: RendererSettings {
+                shadow: Some(ShadowSettings::default()),
+                ..fragment_shading()
+            },
+            shader: pbr_shader,
+            lights: vec![
+                Light::Ambient(AmbientLight { intensity: 0.05 }),
+                Light::Point(PointLight {
+                    position: Vec3::new(0.3, 0.6, 0.5),
+                    intensity: 3.0,
+                }),
+            ],
+        },
+    );
+}
+
+#[test]
+fn cornell_box_transparency() {
+    assert_golden_with(
+        "cornell_box_transparency",
+        Scene {
+            model: "assets/cornell-box.gltf",
+            camera_position: Vec3::new(0.0, 0.0, 3.5),
+            camera_target: Vec3::ZERO,
+            settings: fragment_shading(),
+            shader: pbr_shader,
+            lights: default_lights(),
+        },
+        |model| {
+            // 高盒子半透明，矮盒子透明度低于阈值被完全丢弃
+            for mesh in model.meshes[7].iter_mut() {
+                mesh.material.alpha_mode = AlphaMode::Blend;
+                mesh.material.base_color_factor.w = 0.5;
+            }
+            for mesh in model.meshes[6].iter_mut() {
+                mesh.material.alpha_mode = AlphaMode::Mask;
+                mesh.material.base_color_factor.w = 0.3;
+            }
+        },
+    );
+}
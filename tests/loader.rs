@@ -0,0 +1,194 @@
+use std::{fs, path::PathBuf};
+
+use gltf::{buffer, image, mesh::Mode, Gltf, Semantic};
+use tiny_renderer::{
+    loader::{load_glft, load_meshes, load_textures, primitive_indices, LoaderError},
+    mesh::Topology,
+    texture::TextureError,
+};
+
+#[test]
+fn triangle_strip_keeps_winding() {
+    let (topology, indices) = primitive_indices(Mode::TriangleStrip, &[0, 1, 2, 3, 4]);
+    assert_eq!(topology, Topology::Triangles);
+    assert_eq!(indices, vec![0, 1, 2, 1, 3, 2, 2, 3, 4]);
+}
+
+#[test]
+fn triangle_fan() {
+    let (topology, indices) = primitive_indices(Mode::TriangleFan, &[0, 1, 2, 3]);
+    assert_eq!(topology, Topology::Triangles);
+    assert_eq!(indices, vec![1, 2, 0, 2, 3, 0]);
+}
+
+#[test]
+fn line_loop_closes() {
+    let (topology, indices) = primitive_indices(Mode::LineLoop, &[0, 1, 2]);
+    assert_eq!(topology, Topology::Lines);
+    assert_eq!(indices, vec![0, 1, 1, 2, 2, 0]);
+
+    let (_, indices) = primitive_indices(Mode::LineStrip, &[0, 1, 2]);
+    assert_eq!(indices, vec![0, 1, 1, 2]);
+}
+
+#[test]
+fn points() {
+    let (topology, indices) = primitive_indices(Mode::Points, &[3, 1, 2]);
+    assert_eq!(topology, Topology::Points);
+    assert_eq!(indices, vec![3, 1, 2]);
+}
+
+// 将glTF和二进制缓冲写入临时目录，返回glTF文件路径
+fn write_gltf(name: &str, json: &str, buffer: &[u8]) -> String {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("loader");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(format!("{}.bin", name)), buffer).unwrap();
+    let path = dir.join(format!("{}.gltf", name));
+    fs::write(&path, json).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+// 三个顶点的位置（或法线）和一个三角形的索引，attribute为顶点数据的语义
+fn triangle_gltf(name: &str, attribute: &str, indices: [u16; 3]) -> String {
+    let mut buffer = Vec::new();
+    for v in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+        buffer.extend(v.iter().flat_map(|c| c.to_le_bytes()));
+    }
+    buffer.extend(indices.iter().flat_map(|i| i.to_le_bytes()));
+    buffer.extend([0, 0]);
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "uri": "{name}.bin", "byteLength": 44 }}],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 36 }},
+                {{ "buffer": 0, "byteOffset": 36, "byteLength": 6 }}
+            ],
+            "accessors": [
+                {{
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+                }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+            ],
+            "meshes": [{{ "primitives": [{{ "attributes": {{ "{attribute}": 0 }}, "indices": 1 }}] }}],
+            "nodes": [{{ "mesh": 0 }}],
+            "scenes": [{{ "nodes": [0] }}]
+        }}"#
+    );
+    write_gltf(name, &json, &buffer)
+}
+
+#[test]
+fn missing_file_is_an_io_error() {
+    let result = load_glft("assets/does-not-exist.gltf");
+    assert!(
+        matches!(result, Err(LoaderError::Io(_))),
+        "{:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn malformed_json_is_a_parse_error() {
+    let path = write_gltf("malformed", "{ \"asset\": ", &[]);
+    let result = load_glft(&path);
+    assert!(
+        matches!(result, Err(LoaderError::Parse(_))),
+        "{:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn primitive_without_positions_is_rejected() {
+    let path = triangle_gltf("no_positions", "NORMAL", [0, 1, 2]);
+    // glTF校验会拒绝缺少POSITION的图元
+    let result = load_glft(&path);
+    assert!(
+        matches!(result, Err(LoaderError::Parse(_))),
+        "{:?}",
+        result.err()
+    );
+
+    // 跳过校验时由load_primitive检查
+    let gltf = Gltf::from_slice_without_validation(&fs::read(&path).unwrap()).unwrap();
+    let buffer = buffer::Data(fs::read(path.replace(".gltf", ".bin")).unwrap());
+    let result = load_meshes(&gltf.document, &[buffer]);
+    assert!(
+        matches!(
+            result,
+            Err(LoaderError::MissingAttribute(Semantic::Positions))
+        ),
+        "{:?}",
+        result.err()
+    );
+
+    // 同样的文件带有位置时可以正常加载
+    let path = triangle_gltf("triangle", "POSITION", [0, 1, 2]);
+    assert!(load_glft(&path).is_ok());
+}
+
+#[test]
+fn index_past_the_vertex_count_is_rejected() {
+    let path = triangle_gltf("bad_index", "POSITION", [0, 1, 3]);
+    let result = load_glft(&path);
+    assert!(
+        matches!(
+            result,
+            Err(LoaderError::BadIndex {
+                target: "vertex",
+                index: 3,
+                len: 3
+            })
+        ),
+        "{:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn invalid_images_are_texture_errors() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "images": [{ "uri": "unused.png" }],
+        "textures": [{ "source": 0 }]
+    }"#;
+    let document = Gltf::from_slice(json.as_bytes()).unwrap().document;
+    let image = |width, height, len| image::Data {
+        pixels: vec![0; len],
+        format: image::Format::R8G8B8A8,
+        width,
+        height,
+    };
+
+    // 宽为0
+    let result = load_textures(&document, &[image(0, 2, 0)]);
+    assert!(
+        matches!(
+            result,
+            Err(LoaderError::Texture {
+                index: 0,
+                error: TextureError::EmptyImage { .. }
+            })
+        ),
+        "{:?}",
+        result.err()
+    );
+
+    // 像素数据被截断
+    let result = load_textures(&document, &[image(2, 2, 15)]);
+    assert!(
+        matches!(
+            result,
+            Err(LoaderError::Texture {
+                index: 0,
+                error: TextureError::PixelCount { .. }
+            })
+        ),
+        "{:?}",
+        result.err()
+    );
+
+    assert!(load_textures(&document, &[image(2, 2, 16)]).is_ok());
+}
@@ -0,0 +1,237 @@
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use tiny_renderer::{
+    camera::Camera,
+    math::{Mat2, Mat3, Mat4, Quat, Vec2, Vec3, Vec4},
+};
+
+const EPSILON: f32 = 1e-4;
+
+fn assert_vec3(actual: Vec3, expected: Vec3) {
+    assert!(
+        (actual - expected).length() < EPSILON,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+fn assert_mat4(actual: Mat4, expected: Mat4) {
+    let cols = |m: Mat4| [m.x_axis, m.y_axis, m.z_axis, m.w_axis];
+    for (a, e) in cols(actual).into_iter().zip(cols(expected)) {
+        assert!((a - e).length() < EPSILON, "{:?} != {:?}", actual, expected);
+    }
+}
+
+fn assert_quat(actual: Quat, expected: Quat) {
+    // q和-q表示同一个旋转
+    assert!(
+        (actual.dot(expected).abs() - 1.0).abs() < EPSILON,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn mat2_determinant_and_inverse() {
+    let m = Mat2::from_cols(Vec2::new(4.0, 2.0), Vec2::new(7.0, 6.0));
+    assert_eq!(m.determinant(), 10.0);
+    let inv = m.inverse();
+    assert_eq!((inv.x_axis.x, inv.x_axis.y), (0.6, -0.2));
+    assert_eq!((inv.y_axis.x, inv.y_axis.y), (-0.7, 0.4));
+
+    let singular = Mat2::from_cols(Vec2::new(1.0, 2.0), Vec2::new(2.0, 4.0));
+    assert_eq!(singular.determinant(), 0.0);
+    assert!(singular.try_inverse().is_none());
+}
+
+#[test]
+fn mat3_determinant_and_inverse() {
+    // 行：[1 2 3] [0 1 4] [5 6 0]
+    let m = Mat3::from_cols(
+        Vec3::new(1.0, 0.0, 5.0),
+        Vec3::new(2.0, 1.0, 6.0),
+        Vec3::new(3.0, 4.0, 0.0),
+    );
+    assert_eq!(m.determinant(), 1.0);
+    // 行：[-24 18 5] [20 -15 -4] [-5 4 1]
+    let inv = m.inverse();
+    assert_vec3(inv.x_axis, Vec3::new(-24.0, 20.0, -5.0));
+    assert_vec3(inv.y_axis, Vec3::new(18.0, -15.0, 4.0));
+    assert_vec3(inv.z_axis, Vec3::new(5.0, -4.0, 1.0));
+
+    let product = m * inv;
+    assert_vec3(product.x_axis, Vec3::X);
+    assert_vec3(product.y_axis, Vec3::Y);
+    assert_vec3(product.z_axis, Vec3::Z);
+
+    let singular = Mat3::from_cols(Vec3::X, Vec3::Y, Vec3::new(1.0, 1.0, 0.0));
+    assert!(singular.try_inverse().is_none());
+}
+
+#[test]
+fn mat4_determinant_and_inverse() {
+    #[rustfmt::skip]
+    let m = Mat4::from_rows_slice(&[
+        2., 0., 0., 1.,
+        0., 3., 0., 2.,
+        0., 0., 4., 3.,
+        0., 0., 0., 1.,
+    ]);
+    assert_eq!(m.determinant(), 24.0);
+    #[rustfmt::skip]
+    let expected = Mat4::from_rows_slice(&[
+        0.5, 0., 0., -0.5,
+        0., 1. / 3., 0., -2. / 3.,
+        0., 0., 0.25, -0.75,
+        0., 0., 0., 1.,
+    ]);
+    assert_mat4(m.inverse(), expected);
+
+    // 一般矩阵
+    #[rustfmt::skip]
+    let m = Mat4::from_rows_slice(&[
+        1., 1., 1., -1.,
+        1., 1., -1., 1.,
+        1., -1., 1., 1.,
+        -1., 1., 1., 1.,
+    ]);
+    assert_eq!(m.determinant(), -16.0);
+    assert_mat4(m * m.inverse(), Mat4::IDENTITY);
+    assert_mat4(m.inverse() * m, Mat4::IDENTITY);
+
+    // 投影到xy平面
+    #[rustfmt::skip]
+    let singular = Mat4::from_rows_slice(&[
+        1., 0., 0., 0.,
+        0., 1., 0., 0.,
+        0., 0., 0., 0.,
+        0., 0., 0., 1.,
+    ]);
+    assert_eq!(singular.determinant(), 0.0);
+    assert!(singular.try_inverse().is_none());
+}
+
+#[test]
+fn look_at_matches_camera_view_transformation() {
+    let eye = Vec3::new(1.0, 2.0, 3.0);
+    let mut camera = Camera::new(0.1, 100.0, 1.5, 1.0, eye);
+    camera.look_at(Vec3::ZERO, Vec3::Y);
+    let view = Mat4::look_at(eye, Vec3::ZERO, Vec3::Y);
+    assert_mat4(view, camera.view_transformation());
+
+    // 视图空间中相机位于原点，目标在-z方向
+    assert_vec3((view * eye.extend(1.0)).truncate(), Vec3::ZERO);
+    let target = (view * Vec4::W).truncate();
+    assert_vec3(target, Vec3::new(0.0, 0.0, -eye.length()));
+    // 逆矩阵将视图空间变换回世界空间
+    assert_vec3((view.inverse() * Vec4::W).truncate(), eye);
+}
+
+#[test]
+fn projection_builders() {
+    let (fov, aspect, near, far) = (60.0f32.to_radians(), 1.5, 0.1, 100.0);
+    let camera = Camera::new(near, far, aspect, fov, Vec3::ZERO);
+    let perspective = Mat4::perspective(fov, aspect, near, far);
+    assert_mat4(
+        perspective,
+        camera.frustum.persp_projection_transformation(),
+    );
+    // 近平面映射到z = 1，远平面映射到z = -1
+    let ndc = |m: Mat4, z: f32| (m * Vec4::new(0.0, 0.0, z, 1.0)).to_cartesian_point();
+    assert!((ndc(perspective, -near).z - 1.0).abs() < EPSILON);
+    assert!((ndc(perspective, -far).z + 1.0).abs() < EPSILON);
+
+    let infinite = Mat4::perspective_infinite(fov, aspect, near);
+    assert!((ndc(infinite, -near).z - 1.0).abs() < EPSILON);
+    assert!((ndc(infinite, -1e7).z + 1.0).abs() < EPSILON);
+    // 远平面很远时趋近于无穷远投影
+    assert_mat4(Mat4::perspective(fov, aspect, near, 1e9), infinite);
+
+    let half_height = camera.frustum.height_near() / 2.0;
+    let half_width = camera.frustum.width_near() / 2.0;
+    assert_mat4(
+        Mat4::orthographic(
+            -half_width,
+            half_width,
+            -half_height,
+            half_height,
+            near,
+            far,
+        ),
+        camera.frustum.ortho_projection_transformation(),
+    );
+    // 非对称的边界
+    let ortho = Mat4::orthographic(0.0, 4.0, -1.0, 3.0, 1.0, 3.0);
+    assert_vec3(
+        (ortho * Vec4::new(0.0, 3.0, -1.0, 1.0)).truncate(),
+        Vec3::new(-1.0, 1.0, 1.0),
+    );
+    assert_vec3(
+        (ortho * Vec4::new(4.0, -1.0, -3.0, 1.0)).truncate(),
+        Vec3::new(1.0, -1.0, -1.0),
+    );
+}
+
+#[test]
+fn quat_slerp_and_nlerp() {
+    let start = Quat::IDENTITY;
+    let end = Quat::from_axis_angle(Vec3::Z, FRAC_PI_2);
+    assert_quat(start.slerp(end, 0.0), start);
+    assert_quat(start.slerp(end, 1.0), end);
+    let half = start.slerp(end, 0.5);
+    assert_quat(half, Quat::from_axis_angle(Vec3::Z, FRAC_PI_4));
+    assert_vec3(half * Vec3::X, Vec3::new(0.5f32.sqrt(), 0.5f32.sqrt(), 0.0));
+    // 匀速：1/3处旋转30°
+    assert_quat(
+        start.slerp(end, 1.0 / 3.0),
+        Quat::from_axis_angle(Vec3::Z, PI / 6.0),
+    );
+    // 沿较短的路径（-end与end表示同一旋转）
+    assert_quat(start.slerp(end * -1.0, 0.5), half);
+
+    let nlerp = start.nlerp(end, 0.5);
+    assert!(nlerp.is_normalized());
+    // 对称的中点与slerp相同
+    assert_quat(nlerp, half);
+}
+
+#[test]
+fn quat_euler_conversion() {
+    assert_quat(
+        Quat::from_euler(FRAC_PI_2, 0.0, 0.0),
+        Quat::from_axis_angle(Vec3::X, FRAC_PI_2),
+    );
+    assert_quat(
+        Quat::from_euler(0.0, FRAC_PI_2, 0.0),
+        Quat::from_axis_angle(Vec3::Y, FRAC_PI_2),
+    );
+    // 先绕x轴再绕z轴
+    let q = Quat::from_euler(FRAC_PI_2, 0.0, FRAC_PI_2);
+    let expected =
+        Quat::from_axis_angle(Vec3::Z, FRAC_PI_2) * Quat::from_axis_angle(Vec3::X, FRAC_PI_2);
+    assert_quat(q, expected);
+    assert_vec3(q * Vec3::Y, Vec3::Z);
+
+    let (x, y, z) = (0.3, -0.7, 1.2);
+    let (ex, ey, ez) = Quat::from_euler(x, y, z).to_euler();
+    assert!((ex - x).abs() < EPSILON && (ey - y).abs() < EPSILON && (ez - z).abs() < EPSILON);
+}
+
+#[test]
+fn quat_from_rotation_arc() {
+    let q = Quat::from_rotation_arc(Vec3::X, Vec3::Y);
+    assert_quat(q, Quat::from_axis_angle(Vec3::Z, FRAC_PI_2));
+
+    let from = Vec3::new(1.0, 2.0, -2.0).normalize();
+    let to = Vec3::new(-3.0, 0.0, 4.0).normalize();
+    assert_vec3(Quat::from_rotation_arc(from, to) * from, to);
+
+    // 方向相反
+    let q = Quat::from_rotation_arc(Vec3::Z, Vec3::NEG_Z);
+    assert!(q.is_normalized());
+    assert_vec3(q * Vec3::Z, Vec3::NEG_Z);
+
+    assert_quat(Quat::from_rotation_arc(from, from), Quat::IDENTITY);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use common::camera;
+use tiny_renderer::{
+    loader::load_glft, math::Vec3, mesh::Mesh, renderer::RendererSettings, scene::Scene,
+    texture::TextureStorage, util::seed_rand_color,
+};
+
+// 带索引缓冲的Suzanne（2012个顶点，11808个索引）
+const MONKEY: &str = "assets/monkey/monkey.gltf";
+
+fn load_monkey() -> (Scene, TextureStorage) {
+    load_glft(MONKEY).unwrap()
+}
+
+// 按索引展开为不带索引缓冲的网格
+fn deindex(mesh: &Mesh) -> Mesh {
+    Mesh {
+        vertices: (0..mesh.index_count())
+            .map(|i| mesh.vertices[mesh.index(i)])
+            .collect(),
+        indices: None,
+        ..mesh.clone()
+    }
+}
+
+// 渲染场景，返回帧缓冲和顶点着色器的调用次数
+fn render(scene: &Scene, texture_storage: &TextureStorage) -> (Vec<u8>, usize) {
+    let settings = RendererSettings {
+        vertex_color_interp: true,
+        ..Default::default()
+    };
+    let mut renderer = common::renderer(
+        camera(Vec3::new(0.0, 0.0, 3.0), 0.1, 60.0, 1.0),
+        64,
+        64,
+        settings,
+    );
+    let invocations = Arc::new(AtomicUsize::new(0));
+    let counter = invocations.clone();
+    renderer.vertex_shader = Some(Box::new(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }));
+    renderer.draw_scene(scene, &[], texture_storage);
+    let count = invocations.load(Ordering::Relaxed);
+    (renderer.frame_buffer, count)
+}
+
+#[test]
+fn loader_keeps_index_buffer() {
+    let (scene, _) = load_monkey();
+    let mesh = &scene.meshes[0][0];
+    let indices = mesh.indices.as_ref().unwrap();
+    assert_eq!(indices.len() % 3, 0);
+    assert!(indices.iter().all(|&i| (i as usize) < mesh.vertices.len()));
+    // 共享的顶点只存储一次
+    assert!(mesh.vertices.len() * 5 < indices.len());
+}
+
+#[test]
+fn each_vertex_is_shaded_once() {
+    let (scene, texture_storage) = load_monkey();
+    let mesh = &scene.meshes[0][0];
+    let (indexed, invocations) = render(&scene, &texture_storage);
+    assert_eq!(invocations, mesh.vertices.len());
+
+    // 与展开后的网格渲染结果相同，展开后每个三角形的顶点各着色一次
+    let mut deindexed_scene = scene.clone();
+    deindexed_scene.meshes[0][0] = deindex(mesh);
+    let (deindexed, invocations) = render(&deindexed_scene, &texture_storage);
+    assert_eq!(invocations, mesh.index_count());
+    assert!(indexed == deindexed);
+}
+
+#[test]
+fn random_vertex_colors_depend_only_on_the_seed_and_index() {
+    let colors = |scene: &Scene| -> Vec<_> {
+        scene.meshes[0][0]
+            .vertices
+            .iter()
+            .map(|v| v.color.unwrap().to_rgba8())
+            .collect()
+    };
+    seed_rand_color(Some(7));
+    let (scene, _) = load_monkey();
+    let expected = colors(&scene);
+
+    // 之前加载过其他模型不影响颜色
+    seed_rand_color(Some(7));
+    load_monkey();
+    let (scene, _) = load_monkey();
+    seed_rand_color(None);
+    assert_eq!(colors(&scene), expected);
+}
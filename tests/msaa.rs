@@ -0,0 +1,86 @@
+mod common;
+
+use common::{camera, colored_mesh, render};
+use tiny_renderer::{
+    color::Color,
+    math::Vec3,
+    msaa::SampleCount,
+    renderer::{Renderer, RendererSettings},
+};
+
+const SIZE: u32 = 32;
+
+fn render_triangle(msaa: SampleCount) -> Renderer {
+    let mesh = colored_mesh(
+        &[
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(1.0, -0.7, 0.0),
+            Vec3::new(0.1, 1.0, 0.0),
+        ],
+        Color::WHITE,
+    );
+    // 透明背景，resolve后的透明度即为覆盖率
+    let settings = RendererSettings {
+        clear_color: Color::TRANSPARENT,
+        vertex_color_interp: true,
+        msaa,
+        ..Default::default()
+    };
+    render(
+        camera(Vec3::new(0.0, 0.0, 3.0), 0.1, 60.0, 1.0),
+        SIZE,
+        settings,
+        vec![mesh],
+    )
+}
+
+fn alphas(renderer: &Renderer) -> Vec<u8> {
+    renderer
+        .frame_buffer
+        .chunks_exact(4)
+        .map(|p| p[3])
+        .collect()
+}
+
+#[test]
+fn sample_positions_lie_inside_the_pixel() {
+    for count in [1, 2, 4, 8, 16] {
+        let msaa = SampleCount::from_count(count).unwrap();
+        assert_eq!(msaa.count(), count as usize);
+        for offset in msaa.positions() {
+            assert!(offset.x.abs() <= 0.5 && offset.y.abs() <= 0.5);
+        }
+    }
+    assert_eq!(SampleCount::from_count(3), None);
+}
+
+#[test]
+fn single_sample_has_hard_edges() {
+    let renderer = render_triangle(SampleCount::X1);
+    assert!(alphas(&renderer).iter().all(|&a| a == 0 || a == 255));
+}
+
+#[test]
+fn edges_are_resolved_to_partial_coverage() {
+    let renderer = render_triangle(SampleCount::X8);
+    let coverage = alphas(&renderer);
+    let partial = coverage.iter().filter(|&&a| a != 0 && a != 255).count();
+    assert!(partial > 0);
+    // 三角形内部完全覆盖
+    let center = ((SIZE / 2) * SIZE + SIZE / 2) as usize;
+    assert_eq!(coverage[center], 255);
+
+    // 覆盖率与单采样结果大致相同
+    let single: u32 = alphas(&render_triangle(SampleCount::X1))
+        .iter()
+        .map(|&a| a as u32)
+        .sum();
+    let multi: u32 = coverage.iter().map(|&a| a as u32).sum();
+    assert!((single as f32 / multi as f32 - 1.0).abs() < 0.1);
+
+    assert_eq!(renderer.depth_buffer.len(), (SIZE * SIZE * 8) as usize);
+    assert_eq!(
+        renderer.depth_frame_buffer().data.len(),
+        (SIZE * SIZE * 4) as usize
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use common::camera;
+use gltf::material::AlphaMode;
+use tiny_renderer::{
+    light::{AmbientLight, Light, PointLight},
+    loader::load_glft,
+    math::Vec3,
+    msaa::SampleCount,
+    renderer::{Renderer, RendererSettings, Scissor, TransparencyMode},
+    shader::pbr_shader,
+    shadow::ShadowSettings,
+};
+
+// 宽高不是块大小的整数倍，边缘有不完整的块
+const WIDTH: u32 = 150;
+const HEIGHT: u32 = 100;
+
+fn render(settings: RendererSettings) -> Renderer {
+    let (mut scene, texture_storage) = load_glft("assets/cornell-box.gltf").unwrap();
+    for mesh in scene.meshes[7].iter_mut() {
+        mesh.material.alpha_mode = AlphaMode::Blend;
+        mesh.material.base_color_factor.w = 0.5;
+    }
+    for mesh in scene.meshes[6].iter_mut() {
+        mesh.material.alpha_mode = AlphaMode::Mask;
+        mesh.material.base_color_factor.w = 0.3;
+    }
+    let camera = camera(
+        Vec3::new(0.0, 0.0, 3.5),
+        0.1,
+        60.0,
+        WIDTH as f32 / HEIGHT as f32,
+    );
+    let lights = [
+        Light::Ambient(AmbientLight { intensity: 0.2 }),
+        Light::Point(PointLight {
+            position: Vec3::new(0.0, 0.8, 0.5),
+            intensity: 10.0,
+        }),
+    ];
+    let mut renderer = common::renderer(camera, WIDTH, HEIGHT, settings);
+    renderer.fragment_shader = Some(pbr_shader());
+    renderer.draw_scene(&scene, &lights, &texture_storage);
+    renderer
+}
+
+fn assert_matches_serial(settings: RendererSettings) {
+    let serial = render(settings);
+    let parallel = render(RendererSettings {
+        parallel: true,
+        ..settings
+    });
+    assert!(serial
+        .frame_buffer
+        .chunks(4)
+        .any(|rgba| rgba[..3] != [0, 0, 0]));
+    assert!(serial.frame_buffer == parallel.frame_buffer);
+    assert!(serial.sample_buffer == parallel.sample_buffer);
+    assert!(serial.depth_buffer == parallel.depth_buffer);
+}
+
+#[test]
+fn tiled_rasterization_matches_serial() {
+    let settings = RendererSettings {
+        fragment_shading: true,
+        shadow: Some(ShadowSettings::default()),
+        ..Default::default()
+    };
+    assert_matches_serial(settings);
+    // 裁剪矩形跨越多个块
+    assert_matches_serial(RendererSettings {
+        scissor: Some(Scissor::new(20, 10, 90, 70)),
+        ..settings
+    });
+}
+
+#[test]
+fn tiled_rasterization_matches_serial_with_msaa_and_oit() {
+    assert_matches_serial(RendererSettings {
+        fragment_shading: true,
+        msaa: SampleCount::X4,
+        transparency: TransparencyMode::LinkedList,
+        ..Default::default()
+    });
+}
@@ -0,0 +1,71 @@
+use tiny_renderer::{
+    framebuffer::FrameBuffer,
+    postprocess::{downsample, fxaa, DownsampleFilter},
+};
+
+// 按像素坐标生成灰度图
+fn gray_image(width: u32, height: u32, gray: impl Fn(u32, u32) -> u8) -> FrameBuffer {
+    let mut data = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let g = gray(x, y);
+            data.extend_from_slice(&[g, g, g, 255]);
+        }
+    }
+    FrameBuffer::new(width, height, data)
+}
+
+fn gray_at(frame: &FrameBuffer, x: u32, y: u32) -> u8 {
+    frame.data[((y * frame.width + x) * 4) as usize]
+}
+
+#[test]
+fn box_downsample_averages_blocks() {
+    // 2x2块中一个白色像素
+    let image = gray_image(4, 2, |x, y| if x % 2 == 0 && y == 0 { 255 } else { 0 });
+    let result = downsample(&image, 2, DownsampleFilter::Box);
+    assert_eq!((result.width, result.height), (2, 1));
+    assert_eq!(gray_at(&result, 0, 0), 64);
+    assert_eq!(gray_at(&result, 1, 0), 64);
+    assert_eq!(result.data[3], 255);
+}
+
+#[test]
+fn lanczos_downsample_preserves_flat_regions() {
+    let image = gray_image(12, 12, |_, _| 100);
+    let result = downsample(&image, 3, DownsampleFilter::Lanczos);
+    assert_eq!((result.width, result.height), (4, 4));
+    assert!(result
+        .data
+        .chunks_exact(4)
+        .all(|p| p == [100, 100, 100, 255]));
+
+    // 竖直边缘：远离边缘的像素保持原值，边缘两侧仍然锐利
+    let image = gray_image(24, 6, |x, _| if x < 12 { 0 } else { 255 });
+    let result = downsample(&image, 3, DownsampleFilter::Lanczos);
+    assert_eq!(gray_at(&result, 0, 1), 0);
+    assert_eq!(gray_at(&result, 7, 1), 255);
+    let left = gray_at(&result, 3, 1);
+    let right = gray_at(&result, 4, 1);
+    assert!(left < 64 && right > 191);
+}
+
+#[test]
+fn fxaa_smooths_staircase_edges() {
+    // 平坦的图像保持不变
+    let flat = gray_image(8, 8, |_, _| 128);
+    assert_eq!(fxaa(&flat).data, flat.data);
+
+    // 斜率较小的锯齿边缘
+    let image = gray_image(32, 16, |x, y| if y * 4 < x { 255 } else { 0 });
+    let result = fxaa(&image);
+    let smoothed = result
+        .data
+        .chunks_exact(4)
+        .filter(|p| p[0] != 0 && p[0] != 255)
+        .count();
+    assert!(smoothed > 0);
+    // 远离边缘的像素不受影响
+    assert_eq!(gray_at(&result, 31, 0), 255);
+    assert_eq!(gray_at(&result, 0, 15), 0);
+}
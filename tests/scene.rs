@@ -0,0 +1,136 @@
+use tiny_renderer::{
+    math::{Mat4, Quat, Vec3},
+    mesh::Mesh,
+    scene::{Node, Scene},
+    transform::Transform,
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn transform_point(m: Mat4, p: Vec3) -> Vec3 {
+    (m * p.extend(1.0)).to_cartesian_point()
+}
+
+fn assert_vec3(actual: Vec3, expected: Vec3) {
+    assert!(
+        (actual - expected).length() < EPSILON,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+fn node(transform: Transform, children: Vec<usize>, mesh: Option<usize>) -> Node {
+    Node {
+        transform,
+        children,
+        mesh,
+        ..Default::default()
+    }
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Transform {
+    Transform {
+        translation: Vec3::new(x, y, z),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn child_transform_is_applied_before_parent() {
+    let parent = Transform {
+        translation: Vec3::new(1.0, 0.0, 0.0),
+        rotation: Quat::from_axis_angle(Vec3::Z, 90.0f32.to_radians()),
+        scale: Vec3::splat(2.0),
+    };
+    let child = Transform {
+        translation: Vec3::new(0.0, 1.0, 0.0),
+        scale: Vec3::splat(0.5),
+        ..Default::default()
+    };
+    let scene = Scene {
+        meshes: vec![vec![Mesh::default()]],
+        nodes: vec![node(parent, vec![1], None), node(child, vec![], Some(0))],
+        roots: vec![0],
+    };
+
+    let world_transformations = scene.world_transformations();
+    let p = Vec3::new(1.0, 0.0, 0.0);
+    // 父节点：缩放(2, 0, 0)，绕z轴旋转(0, 2, 0)，平移(1, 2, 0)
+    assert_vec3(
+        transform_point(world_transformations[0].unwrap(), p),
+        Vec3::new(1.0, 2.0, 0.0),
+    );
+    // 子节点局部(0.5, 1, 0)，再经父节点变换：缩放(1, 2, 0)，旋转(-2, 1, 0)，平移(-1, 1, 0)
+    assert_vec3(
+        transform_point(world_transformations[1].unwrap(), p),
+        Vec3::new(-1.0, 1.0, 0.0),
+    );
+
+    let instances = scene.instances();
+    assert_eq!(instances.len(), 1);
+    assert_vec3(
+        transform_point(instances[0].1, p),
+        Vec3::new(-1.0, 1.0, 0.0),
+    );
+}
+
+#[test]
+fn mesh_shared_by_two_nodes_gives_two_instances() {
+    let scene = Scene {
+        meshes: vec![vec![Mesh::default()]],
+        nodes: vec![
+            node(translation(-1.0, 0.0, 0.0), vec![], Some(0)),
+            node(translation(2.0, 0.0, 0.0), vec![], Some(0)),
+        ],
+        roots: vec![0, 1],
+    };
+
+    let instances = scene.instances();
+    assert_eq!(instances.len(), 2);
+    // 两个实例引用同一网格，模型矩阵不同
+    assert!(std::ptr::eq(instances[0].0, instances[1].0));
+    assert_vec3(
+        transform_point(instances[0].1, Vec3::ZERO),
+        Vec3::new(-1.0, 0.0, 0.0),
+    );
+    assert_vec3(
+        transform_point(instances[1].1, Vec3::ZERO),
+        Vec3::new(2.0, 0.0, 0.0),
+    );
+}
+
+#[test]
+fn every_root_is_traversed() {
+    let scene = Scene {
+        meshes: vec![vec![Mesh::default()], vec![Mesh::default()]],
+        nodes: vec![
+            node(translation(0.0, 1.0, 0.0), vec![], Some(0)),
+            // 不可达的节点
+            node(translation(0.0, 2.0, 0.0), vec![], Some(0)),
+            node(translation(0.0, 0.0, 3.0), vec![3], None),
+            node(translation(1.0, 0.0, 0.0), vec![], Some(1)),
+        ],
+        roots: vec![0, 2],
+    };
+
+    let world_transformations = scene.world_transformations();
+    assert!(world_transformations[1].is_none());
+    assert_vec3(
+        transform_point(world_transformations[3].unwrap(), Vec3::ZERO),
+        Vec3::new(1.0, 0.0, 3.0),
+    );
+
+    let instances = scene.instances();
+    assert_eq!(instances.len(), 2);
+    assert!(std::ptr::eq(instances[0].0, scene.meshes[0].as_slice()));
+    assert_vec3(
+        transform_point(instances[0].1, Vec3::ZERO),
+        Vec3::new(0.0, 1.0, 0.0),
+    );
+    assert!(std::ptr::eq(instances[1].0, scene.meshes[1].as_slice()));
+    assert_vec3(
+        transform_point(instances[1].1, Vec3::ZERO),
+        Vec3::new(1.0, 0.0, 3.0),
+    );
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{camera, colored_mesh, render};
+use tiny_renderer::{
+    color::Color,
+    math::Vec3,
+    renderer::{Renderer, RendererSettings, Scissor},
+};
+
+const SIZE: u32 = 16;
+
+// 在z = -1平面上渲染远超出视口四条边的白色三角形
+fn render_oversized_triangle(scissor: Option<Scissor>) -> Renderer {
+    let triangle = colored_mesh(
+        &[
+            Vec3::new(-10.0, -10.0, -1.0),
+            Vec3::new(10.0, -10.0, -1.0),
+            Vec3::new(0.0, 10.0, -1.0),
+        ],
+        Color::WHITE,
+    );
+    let settings = RendererSettings {
+        scissor,
+        vertex_color_interp: true,
+        ..Default::default()
+    };
+    render(
+        camera(Vec3::ZERO, 0.1, 90.0, 1.0),
+        SIZE,
+        settings,
+        vec![triangle],
+    )
+}
+
+// 被写入颜色或深度的像素
+fn written_pixels(renderer: &Renderer) -> Vec<(i32, i32)> {
+    let mut pixels = Vec::new();
+    for y in 0..SIZE as i32 {
+        for x in 0..SIZE as i32 {
+            let index = (y * SIZE as i32 + x) as usize;
+            let color = &renderer.frame_buffer[index * 4..index * 4 + 4];
+            if color != Color::BLACK.to_rgba8() || renderer.depth_buffer[index] != f32::MIN {
+                pixels.push((x, y));
+            }
+        }
+    }
+    pixels
+}
+
+#[test]
+fn triangle_past_every_viewport_edge_fills_the_viewport() {
+    let renderer = render_oversized_triangle(None);
+    // 视口变换将NDC [-1, 1]映射到像素中心[0, SIZE - 1]，按左上填充规则不包含右边和下边
+    let expected: Vec<(i32, i32)> = (1..SIZE as i32)
+        .flat_map(|y| (0..SIZE as i32 - 1).map(move |x| (x, y)))
+        .collect();
+    assert_eq!(written_pixels(&renderer), expected);
+}
+
+#[test]
+fn nothing_is_written_outside_the_scissor_rect() {
+    let renderer = render_oversized_triangle(Some(Scissor::new(3, 5, 6, 4)));
+    let pixels = written_pixels(&renderer);
+    assert_eq!(pixels.len(), 6 * 4);
+    assert!(pixels
+        .iter()
+        .all(|&(x, y)| (3..9).contains(&x) && (5..9).contains(&y)));
+
+    // 部分超出视口的裁剪矩形与视口取交集
+    let renderer = render_oversized_triangle(Some(Scissor::new(-4, 10, 8, 20)));
+    let pixels = written_pixels(&renderer);
+    assert_eq!(pixels.len(), 4 * 6);
+    assert!(pixels
+        .iter()
+        .all(|&(x, y)| (0..4).contains(&x) && (10..16).contains(&y)));
+
+    // 与视口不相交的裁剪矩形不绘制任何像素
+    let renderer = render_oversized_triangle(Some(Scissor::new(20, -8, 4, 4)));
+    assert!(written_pixels(&renderer).is_empty());
+}
@@ -0,0 +1,95 @@
+use tiny_renderer::{
+    camera::Camera,
+    light::{Light, PointLight},
+    math::Vec3,
+    mesh::{Mesh, Vertex},
+    renderer::Projection,
+    scene::Scene,
+    shadow::{LightShadow, ShadowMap, ShadowSettings},
+};
+
+// 四边形（顶点按逆时针顺序给出）
+fn quad(corners: [Vec3; 4]) -> Mesh {
+    Mesh {
+        vertices: corners
+            .map(|p| Vertex {
+                position: p.extend(1.0),
+                ..Default::default()
+            })
+            .to_vec(),
+        indices: Some(vec![0, 1, 2, 0, 2, 3]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn cube_shadow_occluder_straddling_the_near_plane() {
+    // 光源下方y = -2处的地面
+    let floor = quad([
+        Vec3::new(-3.0, -2.0, -3.0),
+        Vec3::new(-3.0, -2.0, 3.0),
+        Vec3::new(3.0, -2.0, 3.0),
+        Vec3::new(3.0, -2.0, -3.0),
+    ]);
+    // 平面x + y = -0.5上的窄条，一端在光源上方（-y面相机的后方），另一端在下方
+    let occluder = quad([
+        Vec3::new(-1.0, 0.5, -0.2),
+        Vec3::new(-1.0, 0.5, 0.2),
+        Vec3::new(1.0, -1.5, 0.2),
+        Vec3::new(1.0, -1.5, -0.2),
+    ]);
+    let scene = Scene::from_meshes(vec![floor, occluder]);
+    let light = Light::Point(PointLight {
+        position: Vec3::ZERO,
+        intensity: 1.0,
+    });
+    let settings = ShadowSettings {
+        resolution: 128,
+        ..Default::default()
+    };
+    let mut shadow = LightShadow::new(&light, scene.bounding_sphere().unwrap(), settings).unwrap();
+    shadow.render(&scene);
+
+    // 光线穿过窄条的地面点在阴影中
+    for x in [-1.0, 0.0, 1.0] {
+        let p = Vec3::new(x, -2.0, 0.0);
+        assert_eq!(shadow.visibility(p, 1.0), 0.0, "{:?}", p);
+    }
+    // 窄条两侧的地面点被照亮
+    for p in [Vec3::new(0.0, -2.0, 1.5), Vec3::new(1.0, -2.0, -1.5)] {
+        assert_eq!(shadow.visibility(p, 1.0), 1.0, "{:?}", p);
+    }
+}
+
+#[test]
+fn pcf_samples_past_the_map_edge_use_the_edge_texels() {
+    // 看向-z的正交阴影相机，贴图覆盖x、y ∈ [-1, 1]
+    let camera = Camera::new(
+        1.0,
+        5.0,
+        1.0,
+        90.0f32.to_radians(),
+        Vec3::new(0.0, 0.0, 3.0),
+    );
+    let settings = ShadowSettings {
+        resolution: 8,
+        pcf_radius: 1,
+        ..Default::default()
+    };
+    let mut shadow_map = ShadowMap::new(camera, Projection::Orthographic, settings);
+    // z = 1处超出整个贴图的遮挡物
+    let occluder = quad([
+        Vec3::new(-3.0, -3.0, 1.0),
+        Vec3::new(3.0, -3.0, 1.0),
+        Vec3::new(3.0, 3.0, 1.0),
+        Vec3::new(-3.0, 3.0, 1.0),
+    ]);
+    shadow_map.render(&Scene::from_meshes(vec![occluder]));
+
+    // 贴图左边、上边和左上角的着色点也完全在阴影中
+    // （按左上填充规则，贴图最右一列和最下一行的纹素不被超出视口的遮挡物覆盖）
+    for (x, y) in [(0.0, 0.0), (-0.99, 0.0), (0.0, 0.99), (-0.99, 0.99)] {
+        let p = Vec3::new(x, y, 0.0);
+        assert_eq!(shadow_map.visibility(p, 1.0), 0.0, "{:?}", p);
+    }
+}
@@ -0,0 +1,200 @@
+use gltf::{
+    image::Format,
+    texture::{MagFilter, MinFilter, WrappingMode},
+};
+use tiny_renderer::{
+    math::Vec2,
+    texture::{decode_pixels, wrap, ColorSpace, Sampler, TexCoord, Texture, TextureError},
+};
+
+// 2x2的RGB纹理：黑、白 / 白、黑
+fn checker(mag_filter: MagFilter, min_filter: MinFilter) -> Texture {
+    Texture::new(
+        0,
+        2,
+        2,
+        Format::R8G8B8,
+        ColorSpace::Linear,
+        &[0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 0],
+        sampler(mag_filter, min_filter),
+    )
+    .unwrap()
+}
+
+fn sampler(mag_filter: MagFilter, min_filter: MinFilter) -> Sampler {
+    Sampler {
+        mag_filter: Some(mag_filter),
+        min_filter: Some(min_filter),
+        wrap_s: WrappingMode::Repeat,
+        wrap_t: WrappingMode::Repeat,
+    }
+}
+
+#[test]
+fn mipmap_chain_averages_texels() {
+    let texture = checker(MagFilter::Nearest, MinFilter::Nearest);
+    assert_eq!(texture.level_count(), 2);
+    let level = texture.level(1);
+    assert_eq!((level.width, level.height), (1, 1));
+    assert_eq!(level.data[0].x, 0.5);
+    assert_eq!(level.data[0].w, 1.0);
+}
+
+#[test]
+fn magnification_filters() {
+    // 两个像素中心之间
+    let uv = Vec2::new(0.5, 0.25);
+    let nearest = checker(MagFilter::Nearest, MinFilter::Nearest).sample(TexCoord::new(uv));
+    assert_eq!(nearest.r, 1.0);
+    let linear = checker(MagFilter::Linear, MinFilter::Nearest).sample(TexCoord::new(uv));
+    assert!((linear.r - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn minification_selects_mipmap_level() {
+    let texture = checker(MagFilter::Nearest, MinFilter::NearestMipmapNearest);
+    // 每个屏幕像素跨越两个纹素，lod = 1
+    let texcoord = TexCoord {
+        uv: Vec2::new(0.25, 0.25),
+        ddx: Vec2::new(1.0, 0.0),
+        ddy: Vec2::new(0.0, 1.0),
+    };
+    assert_eq!(texture.lod(texcoord.ddx, texcoord.ddy), 1.0);
+    assert!((texture.sample(texcoord).r - 0.5).abs() < 1e-6);
+
+    // 在第0层和第1层之间插值
+    let texture = checker(MagFilter::Nearest, MinFilter::NearestMipmapLinear);
+    let texcoord = TexCoord {
+        ddx: Vec2::new(0.5f32.sqrt(), 0.0),
+        ddy: Vec2::new(0.0, 0.5f32.sqrt()),
+        ..texcoord
+    };
+    let expected = 0.25;
+    assert!((texture.sample(texcoord).r - expected).abs() < 1e-3);
+}
+
+#[test]
+fn wrap_modes() {
+    let repeat: Vec<usize> = (-5..6).map(|i| wrap(i, 3, WrappingMode::Repeat)).collect();
+    assert_eq!(repeat, vec![1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2]);
+
+    let clamp: Vec<usize> = (-2..5)
+        .map(|i| wrap(i, 3, WrappingMode::ClampToEdge))
+        .collect();
+    assert_eq!(clamp, vec![0, 0, 0, 1, 2, 2, 2]);
+
+    let mirrored: Vec<usize> = (-4..8)
+        .map(|i| wrap(i, 3, WrappingMode::MirroredRepeat))
+        .collect();
+    assert_eq!(mirrored, vec![2, 2, 1, 0, 0, 1, 2, 2, 1, 0, 0, 1]);
+}
+
+#[test]
+fn wrap_modes_per_axis_with_negative_texcoords() {
+    let mut texture = checker(MagFilter::Nearest, MinFilter::Nearest);
+    texture.sampler.wrap_s = WrappingMode::ClampToEdge;
+    texture.sampler.wrap_t = WrappingMode::MirroredRepeat;
+    // s方向钳制到第0列，t方向镜像到第0行
+    let color = texture.sample(TexCoord::new(Vec2::new(-3.7, -0.25)));
+    assert_eq!(color.r, 0.0);
+    // s方向钳制到第1列，t方向(1.25 * 2 = 2.5)镜像到第1行
+    let color = texture.sample(TexCoord::new(Vec2::new(4.2, 1.25)));
+    assert_eq!(color.r, 0.0);
+    let color = texture.sample(TexCoord::new(Vec2::new(4.2, -0.25)));
+    assert_eq!(color.r, 1.0);
+}
+
+#[test]
+fn decode_all_pixel_formats() {
+    let decoded = decode_pixels(Format::R8, ColorSpace::Linear, &[0, 51]);
+    assert_eq!(decoded[1].x, 0.2);
+    assert_eq!((decoded[1].y, decoded[1].z, decoded[1].w), (0.2, 0.2, 1.0));
+
+    let decoded = decode_pixels(Format::R8G8, ColorSpace::Linear, &[255, 0]);
+    assert_eq!((decoded[0].x, decoded[0].w), (1.0, 0.0));
+
+    let decoded = decode_pixels(Format::R8G8B8A8, ColorSpace::Linear, &[255, 0, 0, 51]);
+    assert_eq!((decoded[0].x, decoded[0].y, decoded[0].w), (1.0, 0.0, 0.2));
+
+    let pixels: Vec<u8> = [u16::MAX, 0, u16::MAX / 2 + 1]
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let decoded = decode_pixels(Format::R16G16B16, ColorSpace::Linear, &pixels);
+    assert_eq!((decoded[0].x, decoded[0].y, decoded[0].w), (1.0, 0.0, 1.0));
+    assert!((decoded[0].z - 0.5).abs() < 1e-4);
+
+    let decoded = decode_pixels(Format::R16, ColorSpace::Linear, &u16::MAX.to_ne_bytes());
+    assert_eq!(decoded[0].z, 1.0);
+
+    // HDR纹理保持大于1的值
+    let pixels: Vec<u8> = [4.0f32, 0.5, 0.0, 0.25]
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let decoded = decode_pixels(Format::R32G32B32A32FLOAT, ColorSpace::Linear, &pixels);
+    assert_eq!((decoded[0].x, decoded[0].y, decoded[0].w), (4.0, 0.5, 0.25));
+    let decoded = decode_pixels(Format::R32G32B32FLOAT, ColorSpace::Linear, &pixels[..12]);
+    assert_eq!((decoded[0].x, decoded[0].w), (4.0, 1.0));
+
+    // 非8位格式的纹理可以正常采样
+    let texture = Texture::new(
+        0,
+        1,
+        1,
+        Format::R32G32B32FLOAT,
+        ColorSpace::Linear,
+        &pixels[..12],
+        sampler(MagFilter::Linear, MinFilter::LinearMipmapLinear),
+    )
+    .unwrap();
+    assert_eq!(texture.sample(TexCoord::new(Vec2::new(0.5, 0.5))).r, 4.0);
+}
+
+#[test]
+fn srgb_textures_decode_to_linear() {
+    let pixels = [0, 188, 255, 51];
+    let decoded = decode_pixels(Format::R8G8B8A8, ColorSpace::Srgb, &pixels);
+    assert_eq!((decoded[0].x, decoded[0].z), (0.0, 1.0));
+    // sRGB 188 ≈ 线性0.5
+    assert!((decoded[0].y - 0.5).abs() < 5e-3);
+    // 透明度不做转换
+    assert_eq!(decoded[0].w, 0.2);
+
+    // 浮点格式本身就是线性的
+    let pixels: Vec<u8> = [0.5f32, 2.0, 0.25]
+        .iter()
+        .flat_map(|c| c.to_ne_bytes())
+        .collect();
+    let decoded = decode_pixels(Format::R32G32B32FLOAT, ColorSpace::Srgb, &pixels);
+    assert_eq!((decoded[0].x, decoded[0].y, decoded[0].z), (0.5, 2.0, 0.25));
+}
+
+#[test]
+fn empty_images_are_rejected() {
+    let new = |width, height, pixels: &[u8]| {
+        Texture::new(
+            0,
+            width,
+            height,
+            Format::R8,
+            ColorSpace::Linear,
+            pixels,
+            sampler(MagFilter::Nearest, MinFilter::Nearest),
+        )
+    };
+    assert_eq!(
+        new(0, 4, &[]).unwrap_err(),
+        TextureError::EmptyImage {
+            width: 0,
+            height: 4
+        }
+    );
+    assert!(matches!(
+        new(2, 2, &[0; 3]),
+        Err(TextureError::PixelCount {
+            expected: 4,
+            actual: 3
+        })
+    ));
+}
@@ -0,0 +1,76 @@
+use tiny_renderer::{
+    camera::Camera,
+    math::{Quat, Vec3},
+    mesh::Vertex,
+    renderer::{Renderer, RendererSettings, Viewport},
+    transform::{normal_matrix, scale_mat4, Transform},
+};
+
+const EPSILON: f32 = 1e-5;
+
+// 法线为(1, 1, 0)的斜面
+fn slope() -> [Vertex; 3] {
+    let normal = Some(Vec3::new(1.0, 1.0, 0.0).normalize());
+    [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 1.0),
+    ]
+    .map(|p| Vertex {
+        position: p.extend(1.0),
+        normal,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn normal_matrix_keeps_normals_perpendicular() {
+    let model = Transform {
+        translation: Vec3::new(1.0, 2.0, 3.0),
+        rotation: Quat::from_axis_angle(Vec3::Z, 0.3),
+        scale: Vec3::new(3.0, 0.5, 1.0),
+    }
+    .to_mat4();
+    let [a, b, c] = slope().map(|v| (model * v.position).truncate());
+    let n = normal_matrix(model) * Vec3::new(1.0, 1.0, 0.0);
+    assert!(n.dot(b - a).abs() < EPSILON);
+    assert!(n.dot(c - a).abs() < EPSILON);
+
+    // 只有旋转时与模型矩阵相同
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0).normalize(), 1.0).to_mat4();
+    let n = Vec3::new(0.0, 0.6, 0.8);
+    assert!(
+        (normal_matrix(rotation) * n - (rotation * n.extend(0.0)).truncate()).length() < EPSILON
+    );
+
+    // 不可逆时不会产生NaN
+    let flatten = scale_mat4(Vec3::new(1.0, 1.0, 0.0));
+    assert!((normal_matrix(flatten) * Vec3::Z).is_finite());
+}
+
+#[test]
+fn model_transformation_produces_unit_world_normals() {
+    let camera = Camera::new(0.1, 10.0, 1.0, 1.0, Vec3::new(0.0, 0.0, 3.0));
+    let renderer = Renderer::new(
+        camera,
+        Viewport::new(0, 0, 4, 4),
+        RendererSettings::default(),
+    );
+    let model = scale_mat4(Vec3::new(4.0, 1.0, 1.0));
+    let mut vertices = slope();
+    renderer.apply_model_transformation(&mut vertices, model);
+
+    let [a, b, c] = vertices.map(|v| v.position.truncate());
+    // 世界空间的面法线
+    let expected = (b - a).cross(c - a).normalize();
+    for vertex in vertices {
+        let n = vertex.normal.unwrap();
+        assert!((n.length() - 1.0).abs() < EPSILON);
+        assert!(
+            (n - expected).length() < EPSILON,
+            "{:?} != {:?}",
+            n,
+            expected
+        );
+    }
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{camera, colored_mesh, render};
+use gltf::material::AlphaMode;
+use tiny_renderer::{
+    color::Color,
+    material::Material,
+    math::Vec3,
+    mesh::Mesh,
+    renderer::{Renderer, RendererSettings, TransparencyMode},
+};
+
+const SIZE: u32 = 40;
+
+// x方向从x0到x1、z = slope * x + offset的矩形（正面朝向+z）
+fn quad(x0: f32, x1: f32, slope: f32, offset: f32, color: Color, alpha_mode: AlphaMode) -> Mesh {
+    let corner = |x: f32, y: f32| Vec3::new(x, y, slope * x + offset);
+    let (a, b, c, d) = (
+        corner(x0, -1.0),
+        corner(x1, -1.0),
+        corner(x1, 1.0),
+        corner(x0, 1.0),
+    );
+    Mesh {
+        material: Material {
+            alpha_mode,
+            ..Default::default()
+        },
+        ..colored_mesh(&[a, b, c, a, c, d], color)
+    }
+}
+
+fn render_quads(meshes: Vec<Mesh>, transparency: TransparencyMode) -> Renderer {
+    // 透明背景，合成结果的透明度只来自半透明片段
+    let settings = RendererSettings {
+        clear_color: Color::TRANSPARENT,
+        vertex_color_interp: true,
+        transparency,
+        ..Default::default()
+    };
+    render(
+        camera(Vec3::new(0.0, 0.0, 3.0), 0.1, 60.0, 1.0),
+        SIZE,
+        settings,
+        meshes,
+    )
+}
+
+fn assert_pixel(renderer: &Renderer, x: u32, y: u32, expected: Color) {
+    let index = ((y * SIZE + x) * 4) as usize;
+    let actual = &renderer.frame_buffer[index..index + 4];
+    let expected = expected.to_rgba8();
+    assert!(
+        actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= 2),
+        "({}, {}): {:?} != {:?}",
+        x,
+        y,
+        actual,
+        expected
+    );
+}
+
+// 两个半透明矩形在x = 0处相交，左半边绿色在前，右半边红色在前
+fn intersecting_panes() -> Vec<Mesh> {
+    vec![
+        quad(
+            -1.0,
+            1.0,
+            0.5,
+            0.0,
+            Color::rgba(1.0, 0.0, 0.0, 0.5),
+            AlphaMode::Blend,
+        ),
+        quad(
+            -1.0,
+            1.0,
+            -0.5,
+            0.0,
+            Color::rgba(0.0, 1.0, 0.0, 0.5),
+            AlphaMode::Blend,
+        ),
+    ]
+}
+
+#[test]
+fn linked_list_composites_intersecting_panes() {
+    let renderer = render_quads(intersecting_panes(), TransparencyMode::LinkedList);
+    // 远处的片段先混合：rgb = near * 0.5 + far * 0.25，a = 0.5 + 0.5 * 0.5
+    assert_pixel(&renderer, 12, 20, Color::rgba(0.25, 0.5, 0.0, 0.75));
+    assert_pixel(&renderer, 28, 20, Color::rgba(0.5, 0.25, 0.0, 0.75));
+    assert!(renderer.fragment_lists.is_empty());
+}
+
+#[test]
+fn linked_list_discards_fragments_behind_opaque_geometry() {
+    let mut meshes = intersecting_panes();
+    // 右半边被不透明矩形完全遮挡
+    meshes.push(quad(0.0, 1.0, 0.0, 0.8, Color::BLUE, AlphaMode::Opaque));
+    let renderer = render_quads(meshes, TransparencyMode::LinkedList);
+    assert_pixel(&renderer, 12, 20, Color::rgba(0.25, 0.5, 0.0, 0.75));
+    assert_pixel(&renderer, 28, 20, Color::BLUE);
+}